@@ -13,11 +13,14 @@ use crate::logger::log;
 // the retrieved value is then hashed and compared against the cookie's check.
 // the check is largely pointless, I just feel bad not using the stored value.
 
-pub async fn validate(request: &Request) -> bool {
+/// If the request carries a valid session, returns an identity for it.
+/// The identity is a hash of the check, so it can be stored publicly
+/// (e.g. in a `Commit`) without giving the session away.
+pub async fn identify(request: &Request) -> Option<String> {
     let headers = request.headers();
     let cookie_header = match headers.get("cookie") {
         Ok(Some(v)) => v,
-        _ => return false,
+        _ => return None,
     };
 
     for cookie_str in cookie_header.split(';').map(|s| s.trim()) {
@@ -26,18 +29,15 @@ pub async fn validate(request: &Request) -> bool {
                 let check = c.value().to_owned();
                 return match kv::value(kv::AuthNS::get(&check, "text")).await {
                     Some(v) => {
-                        let code = match v.as_string() {
-                            Some(v) => v,
-                            None => return false
-                        };
-                        return utils::hash(&code) == check
+                        let code = v.as_string()?;
+                        if utils::hash(&code) == check { Some(utils::hash(&check)) } else { None }
                     },
-                    None => false,
+                    None => None,
                 }
             }
         }
     }
-    return false;
+    return None;
 }
 
 pub async fn session<'a>() -> Result<Cookie<'a>, String> {
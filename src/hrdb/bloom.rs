@@ -0,0 +1,100 @@
+use std::fmt;
+use std::collections::HashSet;
+use std::convert::TryInto;
+use sha2::{Sha256, Digest};
+
+/// How many bit positions each word sets.
+const HASHES: u64 = 7;
+
+/// Bits per distinct word, for roughly a 1% false positive rate.
+const BITS_PER_WORD: usize = 10;
+
+/// A bloom filter over the words of a page.
+/// Serialized as the number of bits, a colon, then the bits in hex.
+pub struct Bloom {
+    bits: Vec<u8>,
+}
+
+impl Bloom {
+    pub fn new(words: &[String]) -> Bloom {
+        let distinct = words.iter().collect::<HashSet<&String>>();
+        let bytes    = (distinct.len() * BITS_PER_WORD).div_ceil(8).max(1);
+        let mut bloom = Bloom { bits: vec![0; bytes] };
+
+        for word in distinct.into_iter() {
+            for bit in bloom.positions(word) {
+                bloom.bits[bit / 8] |= 1 << (bit % 8);
+            }
+        }
+        return bloom;
+    }
+
+    /// Reads a serialized filter; an empty string is an empty filter.
+    pub fn parse(serialized: &str) -> Result<Bloom, String> {
+        if serialized.is_empty() {
+            return Ok(Bloom { bits: vec![] });
+        }
+
+        let (_, hex) = serialized.split_once(':')
+            .ok_or("Bloom filter is missing its length")?;
+        if hex.len() % 2 != 0 {
+            return Err("Bloom filter has an odd number of hex digits".to_owned());
+        }
+
+        let bits = (0..hex.len()).step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .ok().ok_or("Bloom filter is not valid hex")?;
+        return Ok(Bloom { bits });
+    }
+
+    /// Double hashing: the i-th position is `h1 + i * h2`.
+    fn positions(&self, word: &str) -> Vec<usize> {
+        let digest = Sha256::digest(word.as_bytes());
+        let h1 = u64::from_le_bytes(digest[0..8].try_into().unwrap());
+        let h2 = u64::from_le_bytes(digest[8..16].try_into().unwrap());
+        let m  = (self.bits.len() * 8) as u64;
+
+        (0..HASHES)
+            .map(|i| (h1.wrapping_add(i.wrapping_mul(h2)) % m) as usize)
+            .collect::<Vec<usize>>()
+    }
+
+    /// False means the word is definitely not present.
+    /// An empty filter knows nothing, so it may contain anything.
+    pub fn may_contain(&self, word: &str) -> bool {
+        if self.bits.is_empty() { return true; }
+        self.positions(word).into_iter()
+            .all(|bit| self.bits[bit / 8] & (1 << (bit % 8)) != 0)
+    }
+}
+
+impl fmt::Display for Bloom {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let hex = self.bits.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+        write!(f, "{}:{}", self.bits.len() * 8, hex)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<String> {
+        text.split_whitespace().map(|w| w.to_owned()).collect()
+    }
+
+    #[test]
+    fn filters_hold_every_word_they_were_made_from() {
+        let words = words("the quick brown fox jumps over the lazy dog");
+        let filter = Bloom::parse(&Bloom::new(&words).to_string()).unwrap();
+        assert!(words.iter().all(|w| filter.may_contain(w)));
+    }
+
+    #[test]
+    fn filters_rule_out_most_other_words() {
+        let filter = Bloom::new(&words("the quick brown fox jumps over the lazy dog"));
+        let misses = (0..1000).filter(|i| !filter.may_contain(&format!("word{}", i))).count();
+        assert!(misses > 900);
+    }
+}
@@ -1,31 +1,362 @@
-use sha2::Digest;
+use std::collections::{HashMap, HashSet};
 use crate::hrdb::utils::*;
+use crate::hrdb::page::{Page, Children};
+use crate::hrdb::commit::Commit;
+use crate::hrdb::paths::Paths;
 
 pub struct Branch {
-    pub versions: Vec<String> // Page
+    pub versions: Vec<String>, // Page
+    pub commits:  Vec<Option<String>>, // Commit
+    pub paths:    Vec<Option<String>>, // Paths
+}
+
+/// Keys HRDB uses for its own bookkeeping, which no branch may be named.
+pub const RESERVED: &[&str] = &["hrdb", "shorthand", "search", "taxonomy", "protected", "releases", "aliases", "garbage", "migrated"];
+
+/// Stands in for the root of a version dropped by garbage collection,
+/// so the versions after it keep their numbers.
+pub const PRUNED: &str = "pruned";
+
+/// A page that could not be merged automatically.
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub id:     String,
+    pub title:  String,
+    pub reason: String,
+}
+
+impl Conflict {
+    fn at(node: &Node, reason: &str) -> Conflict {
+        Conflict {
+            id:     node.page.id(),
+            title:  node.page.title.to_owned(),
+            reason: reason.to_owned(),
+        }
+    }
+}
+
+/// The outcome of merging one branch into another.
+pub enum Merged {
+    /// The address of the merged root, and every page that differs from the head.
+    Clean(String, Vec<Page>),
+    Conflicted(Vec<Conflict>),
+}
+
+/// A page and the id of the page it is nested under.
+struct Node {
+    parent: Option<String>,
+    page:   Page,
+}
+
+/// Walks the tree under a root, indexing every page by id.
+async fn flatten(root: &str) -> Result<HashMap<String, Node>, String> {
+    let mut nodes = HashMap::new();
+    let mut queue = vec![(None, root.to_owned())];
+
+    while let Some((parent, address)) = queue.pop() {
+        let page = Page::from(&address).await?;
+        for (_id, child) in page.children.iter() {
+            queue.push((Some(page.id()), child.to_owned()));
+        }
+        nodes.insert(page.id(), Node { parent, page });
+    }
+
+    return Ok(nodes);
+}
+
+/// Reads every commit a head descends from, through parents and merges.
+/// Commits already in `known` are kept but not walked past,
+/// and commits that can no longer be read end the walk along that line.
+async fn ancestors(head: &str, known: &HashMap<String, Commit>) -> HashMap<String, Commit> {
+    let mut found = HashMap::new();
+    let mut queue = vec![head.to_owned()];
+
+    while let Some(address) = queue.pop() {
+        if found.contains_key(&address) {
+            continue;
+        }
+        if let Some(commit) = known.get(&address) {
+            found.insert(address, commit.clone());
+            continue;
+        }
+        let commit = match Commit::from(&address).await {
+            Ok(c)  => c,
+            Err(_) => continue,
+        };
+        queue.extend(commit.parent.iter().chain(commit.merged.iter()).cloned());
+        found.insert(address, commit);
+    }
+
+    return found;
+}
+
+/// Takes whichever side changed, or `None` if both did.
+pub fn pick<T: PartialEq + Clone>(base: &T, ours: &T, theirs: &T) -> Option<T> {
+    if ours == theirs || theirs == base {
+        Some(ours.clone())
+    } else if ours == base {
+        Some(theirs.clone())
+    } else {
+        None
+    }
 }
 
 impl Branch {
     pub async fn from(name: &str) -> Result<Branch, String> {
-        Ok(Branch { versions: list(name).await? })
+        let lines = list(name).await?;
+        let (versions, commits) = lines.iter()
+            .map(|l| Commit::split(l))
+            .unzip();
+        let paths = lines.iter()
+            .map(|l| Paths::split(l))
+            .collect::<Vec<Option<String>>>();
+        Ok(Branch { versions, commits, paths })
+    }
+
+    /// The branch list, one version per line.
+    pub fn lines(&self) -> Vec<String> {
+        (0..self.versions.len())
+            .map(|i| {
+                std::iter::once(&self.versions[i])
+                    .chain(self.commits[i].iter())
+                    .chain(self.paths[i].iter())
+                    .cloned()
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect::<Vec<String>>()
     }
 
-    pub async fn fork(&self, version: &str, name: String) -> Result<Branch, String> {
+    /// Drops every version before `until`, keeping its slot as `PRUNED`.
+    pub fn prune(&mut self, until: usize) {
+        for i in 0..until.min(self.versions.len()) {
+            self.versions[i] = PRUNED.to_owned();
+            self.commits[i]  = None;
+            self.paths[i]    = None;
+        }
+    }
+
+    /// How many of the oldest versions have been dropped.
+    pub fn pruned(&self) -> usize {
+        self.versions.iter().take_while(|v| *v == PRUNED).count()
+    }
+
+    /// Every version that has not been dropped, oldest first.
+    pub fn live(&self) -> impl DoubleEndedIterator<Item = &String> {
+        self.versions.iter().filter(|v| *v != PRUNED)
+    }
+
+    /// Checks that a name can be used for a new branch.
+    /// Branches share a namespace with HRDB's own keys and every content hash,
+    /// so anything that could collide with either is rejected.
+    pub fn validate(name: &str) -> Result<(), String> {
+        if name.is_empty() {
+            return Err("Branch names can not be empty".to_owned());
+        }
+        if !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_') {
+            return Err("Branch names may only contain lowercase letters, numbers, dashes, and underscores".to_owned());
+        }
+        if RESERVED.contains(&name) {
+            return Err(format!("'{}' is reserved and can not be used as a Branch name", name));
+        }
+        if is_address(name) {
+            return Err("Branch names can not look like a content hash".to_owned());
+        }
+        return Ok(());
+    }
+
+    /// The versions of this branch up to and including `ver_no`, for a new branch.
+    /// Resets bring back old roots, so the version is given by number rather than root.
+    pub async fn fork(&self, ver_no: usize, name: String) -> Result<Branch, String> {
         if let Ok(_) = read(&name).await {
             return Err(format!("A Branch named {} already exists", name));
         }
 
-        let position = self.versions.iter().position(|x| x == version)
-            .ok_or("Could not find the specified version to fork at on this Branch")?;
+        if ver_no >= self.versions.len() {
+            return Err("Could not find the specified version to fork at on this Branch".to_owned());
+        }
+
+        return Ok(Branch {
+            versions: self.versions[..=ver_no].to_vec(),
+            commits:  self.commits[..=ver_no].to_vec(),
+            paths:    self.paths[..=ver_no].to_vec(),
+        });
+    }
 
-        return Ok(Branch { versions: self.versions[..=position].to_vec() });
+    /// The last version of this branch that also exists on the other.
+    pub fn common(&self, other: &Branch) -> Result<String, String> {
+        let theirs = other.live().collect::<HashSet<&String>>();
+        let c = self.live().rev()
+            .find(|v| theirs.contains(v))
+            .ok_or("These Branches do not share a common version")?;
+        return Ok(c.to_string());
     }
 
-    pub fn merge(&mut self, from: &Branch) -> Result<String, String> {
-        // find last common ancestor, favoring self
+    /// The newest version both heads descend from, following merges as well as parents,
+    /// so that changes already merged are not compared again.
+    /// Falls back to the last common version for branches without commits.
+    pub async fn base(&self, other: &Branch) -> Result<String, String> {
+        let (ours, theirs) = match (self.commits.last(), other.commits.last()) {
+            (Some(Some(o)), Some(Some(t))) => (o.to_owned(), t.to_owned()),
+            _ => return self.common(other),
+        };
+
+        let ours   = ancestors(&ours, &HashMap::new()).await;
+        let theirs = ancestors(&theirs, &ours).await;
+
+        // timestamps alone do not order migrated commits, so ties go to the later line
+        let position = |address: &String| {
+            self.commits.iter().position(|c| c.as_ref() == Some(address))
+                .or_else(|| other.commits.iter().position(|c| c.as_ref() == Some(address)))
+        };
+        let best = theirs.iter()
+            .filter(|(address, _)| ours.contains_key(*address))
+            .max_by_key(|(address, commit)| (commit.timestamp, position(address)));
+
+        return match best {
+            Some((_, commit)) => Ok(commit.root.to_owned()),
+            None => self.common(other),
+        };
+    }
+
+    /// Three-way merges the head of `from` into the head of this branch,
+    /// using the newest version both descend from as the base.
+    /// Nothing is committed; the merged tree is only written.
+    pub async fn merge(&self, from: &Branch) -> Result<Merged, String> {
+        let base   = flatten(&self.base(from).await?).await?;
+        let ours   = flatten(&self.head()?).await?;
+        let theirs = flatten(&from.head()?).await?;
+
         // enumerate all page ids
-        // ...
-        todo!()
+        let mut ids = base.keys()
+            .chain(ours.keys())
+            .chain(theirs.keys())
+            .cloned()
+            .collect::<HashSet<String>>()
+            .into_iter()
+            .collect::<Vec<String>>();
+        ids.sort();
+
+        let mut merged:    HashMap<String, Node> = HashMap::new();
+        let mut conflicts: Vec<Conflict>         = vec![];
+
+        for id in ids.iter() {
+            let node = match (base.get(id), ours.get(id), theirs.get(id)) {
+                (_, None, None) => continue,
+                (None, Some(o), None) => Node { parent: o.parent.clone(), page: o.page.clone() },
+                (None, None, Some(t)) => Node { parent: t.parent.clone(), page: t.page.clone() },
+
+                // deleted on one side: only allowed if the other left it untouched
+                (Some(b), Some(o), None) => {
+                    if !o.page.same_body(&b.page) { conflicts.push(Conflict::at(o, "Edited here but deleted on the other Branch")) }
+                    continue;
+                },
+                (Some(b), None, Some(t)) => {
+                    if !t.page.same_body(&b.page) { conflicts.push(Conflict::at(t, "Deleted here but edited on the other Branch")) }
+                    continue;
+                },
+
+                // added on both sides
+                (None, Some(o), Some(t)) => {
+                    if !o.page.same_body(&t.page) || o.parent != t.parent {
+                        conflicts.push(Conflict::at(o, "Added on both Branches with different contents"));
+                        continue;
+                    }
+                    Node { parent: o.parent.clone(), page: o.page.clone() }
+                },
+
+                (Some(b), Some(o), Some(t)) => {
+                    let parent = match pick(&b.parent, &o.parent, &t.parent) {
+                        Some(p) => p,
+                        None    => { conflicts.push(Conflict::at(o, "Moved to different places on both Branches")); continue; },
+                    };
+                    let mut page = o.page.clone();
+                    match (
+                        pick(&b.page.title,   &o.page.title,   &t.page.title),
+                        pick(&b.page.content, &o.page.content, &t.page.content),
+                        pick(&b.page.fields,  &o.page.fields,  &t.page.fields),
+                    ) {
+                        (Some(title), Some(content), Some(fields)) => {
+                            page.title   = title;
+                            page.content = content;
+                            page.fields  = fields;
+                        },
+                        _ => { conflicts.push(Conflict::at(o, "Edited on both Branches")); continue; },
+                    }
+
+                    // the order of the children all three sides have
+                    let shared = |p: &Page| p.children.ids().into_iter()
+                        .filter(|c| b.page.children.contains_key(c) && o.page.children.contains_key(c) && t.page.children.contains_key(c))
+                        .collect::<Vec<String>>();
+                    let (before, here, there) = (shared(&b.page), shared(&o.page), shared(&t.page));
+                    match pick(&before, &here, &there) {
+                        Some(order) if order != here => page.children = t.page.children.clone(),
+                        Some(_) => (),
+                        None    => conflicts.push(Conflict::at(o, "Children reordered differently on both Branches")),
+                    }
+                    Node { parent, page }
+                },
+            };
+            merged.insert(id.to_owned(), node);
+        }
+
+        // rebuild parent -> children from the merged parents
+        let mut nested: HashMap<String, Vec<String>> = HashMap::new();
+        let mut root = None;
+        for (id, node) in merged.iter() {
+            match &node.parent {
+                None => root = Some(id.to_owned()),
+                Some(p) if merged.contains_key(p) => nested.entry(p.to_owned()).or_default().push(id.to_owned()),
+                Some(_) => conflicts.push(Conflict::at(node, "Nested under a Page that was deleted on the other Branch")),
+            }
+        }
+        let root = root.ok_or("Merged tree has no root Page")?;
+
+        // depth-first from the root, anything not reached is in a cycle
+        let mut order = vec![];
+        let mut stack = vec![root.to_owned()];
+        while let Some(id) = stack.pop() {
+            if let Some(c) = nested.get(&id) { stack.extend(c.iter().cloned()); }
+            order.push(id);
+        }
+        if conflicts.is_empty() && order.len() != merged.len() {
+            let reached = order.iter().collect::<HashSet<&String>>();
+            for (id, node) in merged.iter() {
+                if !reached.contains(id) {
+                    conflicts.push(Conflict::at(node, "Moved underneath one of its own descendants"));
+                }
+            }
+        }
+
+        if !conflicts.is_empty() {
+            return Ok(Merged::Conflicted(conflicts));
+        }
+
+        // write the tree bottom-up, children before their parents
+        let mut addresses: HashMap<String, String> = HashMap::new();
+        let mut changed = vec![];
+        for id in order.iter().rev() {
+            let mut page = merged.remove(id).ok_or("Merged Page went missing")?.page;
+
+            // children keep the order picked for the page above,
+            // and children only the other side had follow, by id
+            let mut kept = nested.get(id).cloned().unwrap_or_default();
+            kept.sort();
+            let order = page.children.ids();
+            let mut children = Children::default();
+            for c in order.iter().filter(|c| kept.contains(c)).chain(kept.iter().filter(|c| !order.contains(c))) {
+                children.insert(c.to_owned(), addresses[c].to_owned());
+            }
+            page.children = children;
+
+            addresses.insert(id.to_owned(), write(&page.to_string()?).await?);
+            match ours.get(id) {
+                Some(o) if o.page.same_body(&page) => (),
+                _ => changed.push(page),
+            }
+        }
+
+        return Ok(Merged::Clean(addresses[&root].to_owned(), changed));
     }
 
     pub fn head(&self) -> Result<String, String> {
@@ -0,0 +1,116 @@
+use serde::{Serialize, Deserialize};
+use serde_json;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use crate::hrdb::utils::*;
+use crate::hrdb::commit::Commit;
+use crate::hrdb::branch::PRUNED;
+use crate::hrdb::page::Page;
+use crate::hrdb::paths::Paths;
+use crate::hrdb::release::Releases;
+use crate::hrdb::shorthand::Aliases;
+
+/// The bundle format this version of HRDB writes, and the only one it reads.
+pub const FORMAT: u32 = 1;
+
+/// Everything needed to rebuild a site: every branch list in order,
+/// the shorthand table and its aliases, which branches are protected, every release,
+/// and every object reachable from a version.
+/// Search indexes are left out, and rebuilt the first time each branch is searched.
+#[derive(Serialize, Deserialize)]
+pub struct Bundle {
+    pub format:    u32,
+    pub branches:  Vec<(String, Vec<String>)>, // name, lines
+    pub shorthand: HashMap<String, (usize, String, String)>,
+    #[serde(default)]
+    pub aliases:   Aliases,
+    #[serde(default)]
+    pub protected: Vec<String>,
+    #[serde(default)]
+    pub releases:  Releases,
+    pub objects:   BTreeMap<String, String>, // address -> value
+}
+
+/// What importing a bundle did to each of its branches.
+pub struct Imported {
+    pub objects:  usize,
+    pub branches: Vec<(String, &'static str)>, // name, "created", "fast-forwarded", "up to date", or "diverged"
+    pub skipped:  Vec<String>, // releases naming a version that is not on their branch here
+}
+
+impl Bundle {
+    pub fn from(serialized: &str) -> Result<Bundle, String> {
+        let bundle: Bundle = serde_json::from_str(serialized)
+            .ok().ok_or("Could not deserialize Bundle")?;
+        if bundle.format != FORMAT {
+            return Err(format!("Bundle is in format {}, but only format {} can be read", bundle.format, FORMAT));
+        }
+        return Ok(bundle);
+    }
+
+    pub fn to_string(&self) -> Result<String, String> {
+        let serialized = serde_json::to_string(self)
+            .ok().ok_or("Could not serialize Bundle")?;
+        return Ok(serialized);
+    }
+
+    /// Checks that every object hashes to its address,
+    /// and that every version, commit and index is either in the bundle or already stored,
+    /// along with every page and content beneath each version.
+    pub async fn verify(&self) -> Result<(), String> {
+        for (address, value) in self.objects.iter() {
+            if &hash(value) != address {
+                return Err(format!("Object {} in the Bundle does not hash to its address", address));
+            }
+        }
+
+        let mut walked = HashSet::new();
+        for (name, lines) in self.branches.iter() {
+            for line in lines.iter() {
+                let (root, commit) = Commit::split(line);
+                if root == PRUNED { continue; }
+                for address in commit.into_iter().chain(Paths::split(line)) {
+                    self.find(&address, name).await?;
+                }
+                self.tree(&root, name, &mut walked).await?;
+            }
+        }
+
+        for name in self.protected.iter() {
+            if !self.branches.iter().any(|(b, _)| b == name) {
+                return Err(format!("Bundle protects Branch {}, which is not in the Bundle", name));
+            }
+        }
+        for (name, release) in self.releases.iter() {
+            let listed = self.branches.iter()
+                .find(|(b, _)| b == &release.branch)
+                .is_some_and(|(_, lines)| lines.iter().any(|l| Commit::split(l).0 == release.version));
+            if !listed {
+                return Err(format!("Release {} names a version that is not in the Bundle", name));
+            }
+        }
+        return Ok(());
+    }
+
+    /// An object from the bundle, or failing that, from the store.
+    async fn find(&self, address: &str, branch: &str) -> Result<String, String> {
+        if let Some(value) = self.objects.get(address) {
+            return Ok(value.to_owned());
+        }
+        read(address).await
+            .map_err(|_| format!("Branch {} refers to {}, which is not in the Bundle", branch, address))
+    }
+
+    /// Checks that every page under a root and its content can be found.
+    /// Versions share unchanged subtrees, so a page that was already walked is not walked again.
+    async fn tree(&self, root: &str, branch: &str, walked: &mut HashSet<String>) -> Result<(), String> {
+        let mut queue = vec![root.to_owned()];
+        while let Some(address) = queue.pop() {
+            if !walked.insert(address.to_owned()) { continue; }
+            let page: Page = serde_json::from_str(&self.find(&address, branch).await?)
+                .ok().ok_or(format!("Branch {} refers to {} as a Page, but it is not one", branch, address))?;
+            self.find(&page.content, branch).await?;
+            queue.extend(page.children.values().cloned());
+        }
+        return Ok(());
+    }
+}
@@ -0,0 +1,28 @@
+use crate::hrdb::location::Location;
+
+/// How a single page differs between two versions.
+/// Removed pages are located in the earlier version, everything else in the later one.
+#[derive(Debug, Clone)]
+pub enum Change {
+    Added(Location),
+    Edited(Location),
+    Moved(Location),
+    Removed(Location),
+}
+
+impl Change {
+    pub fn location(&self) -> &Location {
+        match self {
+            Change::Added(l) | Change::Edited(l) | Change::Moved(l) | Change::Removed(l) => l,
+        }
+    }
+
+    pub fn verb(&self) -> &'static str {
+        match self {
+            Change::Added(_)   => "Added",
+            Change::Edited(_)  => "Edited",
+            Change::Moved(_)   => "Moved",
+            Change::Removed(_) => "Removed",
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use serde::{Serialize, Deserialize};
+use serde_json;
+use time::OffsetDateTime;
+use crate::hrdb::utils::*;
+
+/// Who is making a change, and why.
+#[derive(Debug, Clone)]
+pub struct Signature {
+    pub author:  String,
+    pub message: Option<String>,
+}
+
+impl Signature {
+    pub fn new(author: String, message: Option<String>) -> Signature {
+        Signature { author, message }
+    }
+}
+
+/// A version of a branch, and how it came to be.
+/// Branch lists hold the root and commit address of each version on a line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Commit {
+    pub root:      String, // Page
+    pub parent:    Option<String>, // Commit
+    pub timestamp: u64, // milliseconds since the epoch
+    pub author:    String,
+    pub message:   Option<String>,
+    /// The head commit of the branch merged in, if this version is a merge.
+    /// It may have been collected since, if that branch was deleted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merged:    Option<String>, // Commit
+}
+
+impl Commit {
+    pub async fn from(hash: &str) -> Result<Commit, String> {
+        serde_json::from_str(&read(&hash).await?)
+            .ok().ok_or("Could not deserialize Commit".to_owned())
+    }
+
+    pub fn new(root: String, parent: Option<String>, signature: Signature) -> Result<Commit, String> {
+        Ok(Commit {
+            root,
+            parent,
+            timestamp: now()?,
+            author:    signature.author,
+            message:   signature.message,
+            merged:    None,
+        })
+    }
+
+    pub fn to_string(&self) -> Result<String, String> {
+        let serialized = serde_json::to_string_pretty(self)
+            .ok().ok_or("Could not serialize Commit")?;
+        return Ok(serialized);
+    }
+
+    /// Splits a branch list line into its root and commit.
+    /// Lines written before commits existed only hold a root.
+    pub fn split(line: &str) -> (String, Option<String>) {
+        let mut parts = line.split_whitespace();
+        let root   = parts.next().unwrap_or("").to_owned();
+        let commit = parts.next().map(|c| c.to_owned());
+        (root, commit)
+    }
+
+    /// When, by whom, and why a version was made, as listed alongside it.
+    /// Versions without a commit, and commits backfilled for them, were made at an unknown time.
+    pub async fn summary(address: Option<&str>) -> Result<(String, String, String), String> {
+        let commit = match address {
+            Some(a) => Commit::from(a).await?,
+            None    => return Ok(("Unknown".to_owned(), "".to_owned(), "".to_owned())),
+        };
+        return Ok((
+            if commit.timestamp == 0 { "Unknown".to_owned() } else { commit.when() },
+            commit.author.chars().take(8).collect::<String>(),
+            commit.message.unwrap_or_default(),
+        ));
+    }
+
+    /// When the commit was made, e.g. `2020-07-04 13:37`.
+    pub fn when(&self) -> String {
+        OffsetDateTime::from_unix_timestamp((self.timestamp / 1000) as i64)
+            .format("%F %R")
+    }
+}
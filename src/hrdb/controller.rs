@@ -1,15 +1,34 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use sha2::Digest;
 use crate::logger::log;
 use crate::hrdb::{
-    branch::Branch,
-    page::Page,
+    branch::{Branch, Merged, pick},
+    change::Change,
+    search::{Index, Indexes, Hit, tokenize},
+    bloom::Bloom,
+    commit::{Commit, Signature},
+    diff::{self, PageDiff, lines},
+    gc::{self, Report},
+    fsck::{self, Objects},
+    bundle::{self, Bundle, Imported},
+    lease::Lease,
+    paths::Paths,
+    taxonomy::{self, Taxonomy},
+    release::{Release, Releases},
+    page::{self, Page, Children},
     content::Content,
     location::Location,
-    shorthand::Shorthand,
+    shorthand::{Shorthand, Aliases},
     utils,
 };
 
+/// Returned when a change was based on a version that is no longer the head
+/// of its branch, because someone else committed first.
+pub const STALE: &str = "This Branch has changed since you started; your change was not saved";
+
+/// The version garbage collection leaves in place of those it drops.
+pub use crate::hrdb::branch::{Conflict, PRUNED};
+
 // exploration functions
 pub async fn branches() -> Result<Vec<Location>, String> {
     Ok(
@@ -24,11 +43,21 @@ pub async fn versions(location: Location) -> Result<Vec<Location>, String> {
     Ok(
         utils::list(&location.branch()).await?
             .into_iter()
-            .map(|v| Location::from_branch_and_version(location.branch(), v))
+            .map(|l| Location::from_branch_and_version(location.branch(), Commit::split(&l).0))
             .collect::<Vec<Location>>()
     )
 }
 
+/// The address of the commit behind each version, if it has one.
+pub async fn commits(location: Location) -> Result<Vec<Option<String>>, String> {
+    Ok(
+        utils::list(&location.branch()).await?
+            .into_iter()
+            .map(|l| Commit::split(&l).1)
+            .collect::<Vec<Option<String>>>()
+    )
+}
+
 pub async fn head(location: Location) -> Result<Location, String> {
     Ok(
         versions(location).await?.last()
@@ -57,7 +86,15 @@ pub async fn children(location: Location) -> Result<Vec<Location>, String> {
     return Ok(c);
 }
 
+/// Finds a page by id on a version.
+/// Versions with a `Paths` index are looked up directly, older ones are searched.
 pub async fn locate_id(version: Location, id: String) -> Result<Location, String> {
+    if let Some(paths) = paths(&version).await {
+        let path = paths.get(&id)
+            .ok_or("Could not locate a Page with that id for this version")?;
+        return Ok(Location::from_branch_version_and_path(version.branch(), version.version()?, path));
+    }
+
     // breadth-first search
     // would depth-first be faster?
     // they're most likely to be reading a leaf
@@ -78,6 +115,72 @@ pub async fn locate_id(version: Location, id: String) -> Result<Location, String
     return Err("Could not locate a Page with that id for this version".to_owned());
 }
 
+/// The `Paths` index of a version, if it is on its branch and was written with one.
+async fn paths(version: &Location) -> Option<Paths> {
+    let root  = version.version().ok()?;
+    let lines = utils::list(&version.branch()).await.ok()?;
+    let line  = lines.iter().rev().find(|l| Commit::split(l).0 == root)?;
+    Paths::from(&Paths::split(line)?).await.ok()
+}
+
+/// Every page at or beneath a location, depth-first.
+pub async fn descendants(location: Location) -> Result<Vec<Location>, String> {
+    let mut found = vec![];
+    let mut stack = vec![location];
+
+    while let Some(location) = stack.pop() {
+        for child in children(location.clone()).await? {
+            stack.push(child);
+        }
+        found.push(location);
+    }
+
+    return Ok(found);
+}
+
+/// Every page that differs between two versions.
+/// Content addressing means any subtree with the same address in both is skipped.
+pub async fn changes(before: Location, after: Location) -> Result<Vec<Change>, String> {
+    let mut edited  = vec![];
+    let mut added   = HashMap::new();
+    let mut removed = HashMap::new();
+    let mut stack   = vec![(root(before)?, root(after)?)];
+
+    while let Some((b, a)) = stack.pop() {
+        if b.end()? == a.end()? { continue; }
+
+        let old = Page::from(&b.end()?).await?;
+        let new = Page::from(&a.end()?).await?;
+        if !old.same_body(&new) || !old.children.same_order(&new.children) {
+            edited.push(Change::Edited(a.clone()));
+        }
+
+        for (id, address) in new.children.iter() {
+            match old.children.get(id) {
+                Some(o) => stack.push((b.forward(o.to_owned())?, a.forward(address.to_owned())?)),
+                None    => { added.insert(id.to_owned(), a.forward(address.to_owned())?); },
+            }
+        }
+        for (id, address) in old.children.iter() {
+            if !new.children.contains_key(id) {
+                removed.insert(id.to_owned(), b.forward(address.to_owned())?);
+            }
+        }
+    }
+
+    // a page removed from one parent and added to another was moved
+    let mut changes = edited;
+    for (id, location) in added.into_iter() {
+        changes.push(match removed.remove(&id) {
+            Some(_) => Change::Moved(location),
+            None    => Change::Added(location),
+        });
+    }
+    changes.extend(removed.into_values().map(Change::Removed));
+
+    return Ok(changes);
+}
+
 pub async fn locate(version: Location, ids: Vec<String>) -> Result<Location, String> {
     let mut location = root(version)?;
     // log(&format!("ids: {:?}", ids));
@@ -97,9 +200,20 @@ pub async fn locate(version: Location, ids: Vec<String>) -> Result<Location, Str
 
 // modification functions
 
+/// The key recording which migration a store has been brought up to.
+const MIGRATED: &str = "migrated";
+
+/// Bumped whenever `migrate` learns to do something new,
+/// so that stores already brought up to date are not migrated again.
+const MIGRATION: &str = "1";
+
 pub async fn init() -> Result<(), String> {
     if let Ok(_) = utils::read("hrdb").await {
-        return Ok(());
+        if utils::read(MIGRATED).await.ok().as_deref() == Some(MIGRATION) {
+            return Ok(());
+        }
+        migrate().await?;
+        return utils::mutate(MIGRATED, MIGRATION).await;
     }
 
     let content = Content::new("My friend ( ͡° ͜ʖ ͡°) would like you to check back soon.".to_owned());
@@ -113,18 +227,144 @@ pub async fn init() -> Result<(), String> {
     let version = utils::write(&root.to_string()?).await?;
 
     let mut table = HashMap::new();
-    table.insert(root.short(), (0, root.id(), "".to_owned()));
+    table.insert(root.short(), (0, root.id(), bloom(&root).await?));
     Shorthand::wrap(table).write().await?;
 
+    let first = Commit::new(version.clone(), None, Signature::new(
+        "hrdb".to_owned(),
+        Some("Initialized HRDB".to_owned()),
+    ))?;
+    let commit = utils::write(&first.to_string()?).await?;
+    let paths  = utils::write(&Paths::build(&version, None).await?.to_string()?).await?;
+
     utils::ensure("master").await?;
-    utils::push("master", version.clone()).await?;
+    utils::push("master", format!("{} {} {}", version, commit, paths)).await?;
     utils::ensure("hrdb").await?;
     utils::push("hrdb", "master".to_owned()).await?;
+    utils::mutate(MIGRATED, MIGRATION).await?;
+
+    return Ok(());
+}
+
+/// Gives every version written before commits existed a commit,
+/// chained to the version before it, and every version written before
+/// `Paths` indexes existed an index, then rewrites the branch lists to point at them.
+/// Versions that already have both are left alone.
+/// Finally, the head of any branch with pages in an older format is rewritten
+/// in the current one, as a new version.
+pub async fn migrate() -> Result<(), String> {
+    for branch in utils::list("hrdb").await?.iter() {
+        let lease  = Lease::acquire(branch).await?;
+        let result = backfill(branch).await;
+        lease.release().await?;
+        result?;
+    }
+
+    for branch in utils::list("hrdb").await?.iter() {
+        let head = Branch::from(branch).await?.head()?;
+        let root = canonical(&head).await?;
+        if root != head {
+            publish(branch, &head, root, Signature::new(
+                "hrdb".to_owned(),
+                Some("Migrated Pages to the current format".to_owned()),
+            ), None).await?;
+        }
+    }
+
+    Shorthand::settle().await?;
+    return Ok(());
+}
+
+/// Backfills the commits and `Paths` indexes of one branch, as `migrate` describes.
+/// Only called while holding the branch's `Lease`.
+async fn backfill(branch: &str) -> Result<(), String> {
+    let mut lines  = vec![];
+    let mut parent = None;
+    let mut index: Option<String> = None;
+    let mut dirty  = false;
+
+    for line in utils::list(branch).await?.iter() {
+        let (root, commit) = Commit::split(line);
+        if root == PRUNED {
+            lines.push(root);
+            continue;
+        }
+        let commit = match commit {
+            Some(c) => c,
+            None => {
+                let mut migrated = Commit::new(root.clone(), parent.clone(), Signature::new(
+                    "unknown".to_owned(),
+                    Some("Written before commits were recorded".to_owned()),
+                ))?;
+                migrated.timestamp = 0;
+                dirty = true;
+                utils::write(&migrated.to_string()?).await?
+            },
+        };
+        let paths = match Paths::split(line) {
+            Some(p) => p,
+            None => {
+                // built from the version before, which usually shares most of its tree
+                let previous = match &index {
+                    Some(i) => Paths::from(i).await.ok(),
+                    None    => None,
+                };
+                dirty = true;
+                utils::write(&Paths::build(&root, previous.as_ref()).await?.to_string()?).await?
+            },
+        };
+        lines.push(format!("{} {} {}", root, commit, paths));
+        parent = Some(commit);
+        index  = Some(paths);
+    }
 
+    if dirty {
+        utils::mutate(branch, &lines.join("\n")).await?;
+    }
     return Ok(());
 }
 
-pub async fn fork(from: Location, into: Location) -> Result<(), String> {
+/// Rewrites the tree under a root in the current page format,
+/// returning the address of the new root, which is unchanged if every page already was.
+/// Children written before they were ordered are sorted by title.
+async fn canonical(root: &str) -> Result<String, String> {
+    let mut order = vec![];
+    let mut stack = vec![root.to_owned()];
+    while let Some(address) = stack.pop() {
+        let page = Page::from(&address).await?;
+        stack.extend(page.children.values().cloned());
+        order.push((address, page));
+    }
+
+    // children before their parents
+    let mut rewritten: HashMap<String, String> = HashMap::new(); // old address -> new address
+    let mut titles:    HashMap<String, String> = HashMap::new(); // id -> title
+    for (address, mut page) in order.into_iter().rev() {
+        let outdated = utils::hash(&page.to_string()?) != address;
+        for (id, child) in page.children.clone().into_iter() {
+            page.children.insert(id, rewritten[&child].to_owned());
+        }
+        if outdated {
+            page.children.sort_by_key(|id| titles.get(id).cloned().unwrap_or_default());
+        }
+
+        titles.insert(page.id(), page.title.to_owned());
+        let serialized = page.to_string()?;
+        let new = if utils::hash(&serialized) == address {
+            address.to_owned()
+        } else {
+            utils::write(&serialized).await?
+        };
+        rewritten.insert(address, new);
+    }
+
+    return Ok(rewritten[root].to_owned());
+}
+
+/// Starts a new branch from `from`, which is version `ver_no` of its branch.
+pub async fn fork(from: Location, ver_no: usize, into: Location) -> Result<(), String> {
+    Branch::validate(&into.branch())?;
+
     // check that new branch is unique
     // to 'copy' into an existing branch, use merge
     if let Ok(_) = utils::read(&into.branch()).await {
@@ -132,41 +372,256 @@ pub async fn fork(from: Location, into: Location) -> Result<(), String> {
     }
 
     let from_branch  = Branch::from(&from.branch()).await?;
-    let from_version = &from.version()?;
-    let into_branch  = from_branch.fork(from_version, into.branch()).await?;
+    if from_branch.versions.get(ver_no) != Some(&from.version()?) {
+        return Err(format!("Version {} of {} is not the version to fork at", ver_no, from.branch()));
+    }
+    let into_branch  = from_branch.fork(ver_no, into.branch()).await?;
 
     utils::ensure(&into.branch()).await?;
-    utils::append(&into.branch(), into_branch.versions).await?;
+    utils::append(&into.branch(), into_branch.lines()).await?;
+    utils::push("hrdb", into.branch()).await?;
+
+    // start the new branch's search index from the old one
+    let mut indexes = Indexes::read().await?;
+    if let Some((version, index)) = indexes.get(&from.branch()) {
+        indexes.set(into.branch(), version, index);
+        indexes.write().await?;
+    }
 
     return Ok(());
 }
 
-// pub async fn merge(location, location)
+/// The branches that only accept merges.
+pub async fn protected() -> Result<Vec<String>, String> {
+    Ok(utils::list("protected").await.unwrap_or_default())
+}
 
-/// Updates page at location, iterating backwards through path chain.
-async fn commit(location: Location, updated: Page) -> Result<(), String> {
-    // commits can only be applied to the head version of the branch
-    // check that this commit is being applied to the head
-    let head    = head(location.clone()).await?;
-    let version = location.version()?;
-    let ver_no  = versions(location.clone()).await?.len(); // ver_no indexes versions.
-    if version != head.version()? {
-        // return Err(format!("head: {}, version: {}", head, version));
-        return Err("Can only create a Page on latest version of a Branch".to_owned());
+/// Refuses direct changes to a protected branch.
+async fn writable(branch: &str) -> Result<(), String> {
+    if protected().await?.iter().any(|p| p == branch) {
+        return Err(format!("Branch {} is protected, and can only be changed by merging into it", branch));
     }
+    return Ok(());
+}
 
-    // load all the pages and update shorthand
-    let mut pages = vec![];
-    for address in location.path()?.iter() {
-        let page = Page::from(address).await?;
-        if location.branch() == "master" {
-            Shorthand::update(page.short(), ver_no, page.id()).await?;
+/// Marks a branch as protected, or lifts its protection.
+pub async fn protect(location: Location, protect: bool) -> Result<(), String> {
+    let name = location.branch();
+    if !utils::list("hrdb").await?.contains(&name) {
+        return Err(format!("There is no Branch named {}", name));
+    }
+
+    let mut names = protected().await?;
+    names.retain(|p| p != &name);
+    if protect {
+        names.push(name);
+    }
+    utils::mutate("protected", &names.join("\n")).await?;
+    return Ok(());
+}
+
+/// Checks that a branch may be renamed or deleted.
+/// Master is where shorthands and tags point, so it always stays.
+async fn removable(name: &str) -> Result<(), String> {
+    if name == "master" {
+        return Err("The master Branch can not be renamed or deleted".to_owned());
+    }
+    if protected().await?.iter().any(|p| p == name) {
+        return Err(format!("Branch {} is protected; lift its protection first", name));
+    }
+    if !utils::list("hrdb").await?.iter().any(|n| n == name) {
+        return Err(format!("There is no Branch named {}", name));
+    }
+    return Ok(());
+}
+
+/// Removes a branch from the list of branches.
+/// Its versions are left in place until they are collected.
+pub async fn delete_branch(location: Location) -> Result<(), String> {
+    let name = location.branch();
+    removable(&name).await?;
+    if Releases::read().await?.iter().any(|(_, r)| r.branch == name) {
+        return Err(format!("Branch {} has Releases, which can not be removed", name));
+    }
+
+    let lease  = Lease::acquire(&name).await?;
+    let result = unlist(&name).await;
+    lease.release().await?;
+    result?;
+
+    let mut indexes = Indexes::read().await?;
+    indexes.retain(|branch, _| branch != name);
+    indexes.write().await?;
+    return Ok(());
+}
+
+/// Takes a branch off the list of branches, and drops its list of versions.
+/// Only called while holding the branch's `Lease`.
+async fn unlist(name: &str) -> Result<(), String> {
+    let mut names = utils::list("hrdb").await?;
+    names.retain(|n| n != name);
+    utils::mutate("hrdb", &names.join("\n")).await?;
+    utils::remove(name).await?;
+    return Ok(());
+}
+
+/// Moves a branch's list of versions to a new name, in the same place in the list of branches.
+/// Only called while holding the branch's `Lease`.
+async fn relist(old: &str, new: &str) -> Result<(), String> {
+    let lines = Branch::from(old).await?.lines();
+    utils::mutate(new, &lines.join("\n")).await?;
+    let names = utils::list("hrdb").await?.into_iter()
+        .map(|n| if n == old { new.to_owned() } else { n })
+        .collect::<Vec<String>>();
+    utils::mutate("hrdb", &names.join("\n")).await?;
+    utils::remove(old).await?;
+    return Ok(());
+}
+
+/// Gives a branch a new name, keeping its place in the list of branches.
+pub async fn rename_branch(from: Location, into: Location) -> Result<(), String> {
+    let (old, new) = (from.branch(), into.branch());
+    removable(&old).await?;
+    Branch::validate(&new)?;
+    if let Ok(_) = utils::read(&new).await {
+        return Err(format!("A Branch named {} already exists", new));
+    }
+
+    let lease  = Lease::acquire(&old).await?;
+    let result = relist(&old, &new).await;
+    lease.release().await?;
+    result?;
+
+    let mut indexes = Indexes::read().await?;
+    if let Some((version, index)) = indexes.get(&old) {
+        indexes.set(new.to_owned(), version, index);
+    }
+    indexes.retain(|branch, _| branch != old);
+    indexes.write().await?;
+
+    let lease = Lease::acquire("releases").await?;
+    let mut releases = Releases::read().await?;
+    releases.rename(&old, &new);
+    let result = releases.write().await;
+    lease.release().await?;
+    result?;
+    return Ok(());
+}
+
+/// The pages that would keep the head of one branch from merging into the head of another,
+/// found without committing anything.
+pub async fn merge_conflicts(from: Location, into: Location) -> Result<Vec<Conflict>, String> {
+    if from.branch() == into.branch() {
+        return Err("Can not merge a Branch into itself".to_owned());
+    }
+
+    let from_branch = Branch::from(&from.branch()).await?;
+    let into_branch = Branch::from(&into.branch()).await?;
+    return match into_branch.merge(&from_branch).await? {
+        Merged::Conflicted(conflicts) => Ok(conflicts),
+        Merged::Clean(_, _)           => Ok(vec![]),
+    };
+}
+
+/// Merges the head of one branch into the head of another,
+/// committing the result as a new version on `into`.
+/// If the merge can not be completed, the conflicting pages are returned instead.
+pub async fn merge(from: Location, into: Location, signature: Signature) -> Result<Vec<Conflict>, String> {
+    if from.branch() == into.branch() {
+        return Err("Can not merge a Branch into itself".to_owned());
+    }
+
+    let from_branch = Branch::from(&from.branch()).await?;
+    let into_branch = Branch::from(&into.branch()).await?;
+
+    let (root, changed) = match into_branch.merge(&from_branch).await? {
+        Merged::Conflicted(conflicts) => return Ok(conflicts),
+        Merged::Clean(root, changed)  => (root, changed),
+    };
+
+    // already up to date
+    if root == into_branch.head()? {
+        return Ok(vec![]);
+    }
+
+    let merged = from_branch.commits.last().cloned().unwrap_or(None);
+    publish(&into.branch(), &into_branch.head()?, root, signature, merged).await?;
+
+    if into.branch() == "master" {
+        let ver_no = into_branch.versions.len();
+        for page in changed.iter() {
+            Shorthand::update(page.short(), ver_no, page.id(), bloom(page).await?).await?;
         }
-        pages.push(page);
     }
+    return Ok(vec![]);
+}
+
+/// Carries the change made to a page since an earlier version of its branch
+/// onto the same page on the head of another branch, committing it there.
+/// The title, content and fields are each taken from `source` unless the other
+/// branch has changed them too, in which case the conflict is returned instead.
+pub async fn cherry_pick(source: Location, before: Location, into: Location, signature: Signature) -> Result<Vec<Conflict>, String> {
+    if source.branch() == into.branch() {
+        return Err("Can not cherry-pick a change onto the Branch it was made on".to_owned());
+    }
+
+    let id     = source.id().await?;
+    let base   = locate_id(before, id.to_owned()).await
+        .map_err(|_| "This Page did not exist at the earlier version, so there is no change to carry over".to_owned())?;
+    let base   = Page::from(&base.end()?).await?;
+    let theirs = Page::from(&source.end()?).await?;
+
+    let target = locate_id(head(into.clone()).await?, id.to_owned()).await
+        .map_err(|_| format!("This Page does not exist on the head of {}", into.branch()))?;
+    let ours   = Page::from(&target.end()?).await?;
+
+    let mut page = ours.clone();
+    let mut diverged = vec![];
+    match pick(&base.title, &ours.title, &theirs.title) {
+        Some(title) => page.title = title,
+        None        => diverged.push("title"),
+    }
+    match pick(&base.content, &ours.content, &theirs.content) {
+        Some(content) => page.content = content,
+        None          => diverged.push("content"),
+    }
+    match pick(&base.fields, &ours.fields, &theirs.fields) {
+        Some(fields) => page.fields = fields,
+        None         => diverged.push("fields"),
+    }
+
+    if !diverged.is_empty() {
+        return Ok(vec![Conflict {
+            id,
+            title:  ours.title,
+            reason: format!("The {} also changed on {}", diverged.join(" and "), into.branch()),
+        }]);
+    }
+    if page.same_body(&ours) {
+        return Ok(vec![]);
+    }
+
+    commit(target, page, signature).await?;
+    return Ok(vec![]);
+}
+
+/// Checks that a location is on the head version of its branch.
+async fn on_head(location: &Location) -> Result<(), String> {
+    let head = head(location.clone()).await?;
+    if location.version()? != head.version()? {
+        return Err(STALE.to_owned());
+    }
+    return Ok(());
+}
 
-    // Update the potentially changed title of the new page
-    Shorthand::update(updated.short(), ver_no, updated.id()).await?;
+/// Writes the updated page at location, then each of its parents in turn,
+/// returning the address of the new root.
+/// Nothing is pushed to the branch.
+async fn rebuild(location: &Location, updated: Page) -> Result<String, String> {
+    let mut pages = vec![];
+    for address in location.path()?.iter() {
+        pages.push(Page::from(address).await?);
+    }
 
     // get the new address of the page that has been updated
     let mut address   = utils::write(&updated.to_string()?).await?;
@@ -181,7 +636,86 @@ async fn commit(location: Location, updated: Page) -> Result<(), String> {
         child   = parent;
     }
 
-    utils::push(&location.branch(), address).await?;
+    return Ok(address);
+}
+
+/// A serialized bloom filter of every word in a page's title and content,
+/// for storing alongside its `Shorthand`.
+async fn bloom(page: &Page) -> Result<String, String> {
+    let text  = utils::read(&page.content).await?;
+    let words = tokenize(&(page.title.to_owned() + " " + &text));
+    return Ok(Bloom::new(&words).to_string());
+}
+
+/// Makes a new root the head of a branch, recording who made it in a `Commit`,
+/// then brings everything derived from the head up to date.
+/// Once the new root is the head, this succeeds even if that can not be done.
+/// `base` is the head the change was made against; if the head has moved on since,
+/// nothing is published and `STALE` is returned.
+/// `merged` is the head commit of the branch merged in, for merges.
+async fn publish(branch: &str, base: &str, root: String, signature: Signature, merged: Option<String>) -> Result<(), String> {
+    // index the new version against the one it was based on
+    let previous = paths(&Location::from_branch_and_version(branch.to_owned(), base.to_owned())).await;
+    let paths = utils::write(&Paths::build(&root, previous.as_ref()).await?.to_string()?).await?;
+
+    let lease  = Lease::acquire(branch).await?;
+    let result = advance(branch, base, root, paths, signature, merged).await;
+    lease.release().await?;
+    result?;
+
+    // the version has landed, so a failure here is only logged;
+    // both catch up from wherever they were left on the next change
+    if let Err(e) = reindex(Location::from_branch(branch.to_owned())).await {
+        log(&format!("Could not update the search index of {}: {}", branch, e));
+    }
+    if branch == "master" {
+        if let Err(e) = retag().await {
+            log(&format!("Could not update the tags: {}", e));
+        }
+    }
+    return Ok(());
+}
+
+/// Compares the head of a branch against `base`, and swaps in the new root if they match.
+/// Only called while holding the branch's `Lease`.
+async fn advance(branch: &str, base: &str, root: String, paths: String, signature: Signature, merged: Option<String>) -> Result<(), String> {
+    let current = Branch::from(branch).await?;
+    if current.head()? != base {
+        return Err(STALE.to_owned());
+    }
+
+    let parent  = current.commits.last().cloned().unwrap_or(None);
+    let mut commit = Commit::new(root.clone(), parent, signature)?;
+    commit.merged  = merged;
+    let address = utils::write(&commit.to_string()?).await?;
+
+    let mut lines = current.lines();
+    lines.push(format!("{} {} {}", root, address, paths));
+    utils::mutate(branch, &lines.join("\n")).await?;
+    return Ok(());
+}
+
+/// Updates page at location, iterating backwards through path chain.
+async fn commit(location: Location, updated: Page, signature: Signature) -> Result<(), String> {
+    // commits can only be applied to the head version of the branch
+    // check that this commit is being applied to the head
+    writable(&location.branch()).await?;
+    on_head(&location).await?;
+    let ver_no = versions(location.clone()).await?.len(); // ver_no indexes versions.
+
+    let root = rebuild(&location, updated.clone()).await?;
+    publish(&location.branch(), &location.version()?, root, signature, None).await?;
+
+    // update shorthand, once the version it points at exists
+    if location.branch() == "master" {
+        for address in location.path()?.iter() {
+            let page = Page::from(address).await?;
+            Shorthand::update(page.short(), ver_no, page.id(), bloom(&page).await?).await?;
+        }
+
+        // Update the potentially changed title of the new page
+        Shorthand::update(updated.short(), ver_no, updated.id(), bloom(&updated).await?).await?;
+    }
     return Ok(());
 }
 
@@ -192,12 +726,14 @@ pub async fn create(
     title: String,
     content: String,
     fields: HashMap<String, String>,
+    signature: Signature,
 ) -> Result<Location, String> {
     let c       = utils::write(&content.to_string()).await?;
     let new     = Page::new(title, c, fields);
+    unique_slug(&location, &new).await?;
     let address = utils::write(&new.to_string()?).await?;
     let child = location.forward(address)?;
-    commit(child.clone(), new).await?;
+    commit(child.clone(), new, signature).await?;
     return Ok(child);
 }
 
@@ -206,17 +742,263 @@ pub async fn edit(
     title: Option<String>,
     content: Option<String>,
     fields: Option<HashMap<String, String>>,
+    signature: Signature,
 ) -> Result<(), String> {
     let mut page = Page::from(&location.end()?).await?;
 
+    let slug = page.fields.get(page::SLUG).cloned();
+
     if let Some(c) = content { page.content = utils::write(&c.to_string()).await? }
     if let Some(t) = title   { page.title   = t }
     if let Some(f) = fields  { page.fields  = f }
 
-    commit(location, page).await?;
+    if page.fields.get(page::SLUG) != slug.as_ref() {
+        unique_slug(&location, &page).await?;
+    }
+
+    commit(location, page, signature).await?;
+    return Ok(());
+}
+
+/// Checks that no other page on the head of a branch goes by the slug a page asks for.
+/// On master, names other pages used to have are kept for them too.
+async fn unique_slug(location: &Location, page: &Page) -> Result<(), String> {
+    let slug = match page.fields.get(page::SLUG) {
+        Some(s) => s,
+        None    => return Ok(()),
+    };
+    if !page::is_slug(slug) {
+        return Err(format!("The slug '{}' can't be used as a shorthand", slug));
+    }
+    let taken = Err(format!("The slug '{}' is already used by another Page on this Branch", slug));
+
+    if location.branch() == "master" {
+        let owner = Shorthand::read().await?.unwrap().get(slug).map(|(_, id, _)| id.to_owned())
+            .or(Aliases::read().await?.get(slug).cloned());
+        if owner.is_some_and(|id| id != page.id()) {
+            return taken;
+        }
+    }
+
+    let head = head(location.clone()).await?;
+    for l in descendants(root(head)?).await? {
+        let other = Page::from(&l.end()?).await?;
+        if other.id() != page.id() && &other.short() == slug {
+            return taken;
+        }
+    }
+    return Ok(());
+}
+
+/// What has to be done to something kept per page, like a search index,
+/// to bring it from one version of a branch up to date with another:
+/// the ids of the pages to drop, then the pages to read in again.
+/// Moved pages are dropped and read back in, along with everything beneath them.
+async fn touched(before: Location, after: Location) -> Result<(Vec<String>, Vec<Location>), String> {
+    let mut removed = vec![];
+    let mut changed = vec![];
+    for change in changes(before, after).await? {
+        match change {
+            Change::Removed(l) => for d in descendants(l).await? {
+                removed.push(d.id().await?);
+            },
+            Change::Edited(l) => changed.push(l),
+            Change::Added(l) | Change::Moved(l) => changed.extend(descendants(l).await?),
+        }
+    }
+    return Ok((removed, changed));
+}
+
+/// Adds a page to a search index, replacing it if it is already there.
+async fn index(index: &mut Index, location: Location) -> Result<(), String> {
+    let page = Page::from(&location.end()?).await?;
+    let text = utils::read(&page.content).await?;
+    index.insert(page.id(), page.title, page.content, &text);
+    return Ok(());
+}
+
+/// Brings the search index of a branch up to date with its head.
+/// Only the pages that changed since the index was last updated are read,
+/// unless the branch has never been indexed.
+pub async fn reindex(location: Location) -> Result<Index, String> {
+    let head = head(location.clone()).await?;
+    let mut indexes = Indexes::read().await?;
+
+    let (mut updated, (removed, changed)) = match indexes.get(&head.branch()) {
+        Some((version, address)) if version == head.version()? => return Index::from(&address).await,
+        Some((version, address)) => {
+            let before = Location::from_branch_and_version(head.branch(), version);
+            (Index::from(&address).await?, touched(before, head.clone()).await?)
+        },
+        None => (Index::default(), (vec![], descendants(root(head.clone())?).await?)),
+    };
+    for id in removed.iter() {
+        updated.remove(id);
+    }
+    for l in changed.into_iter() {
+        index(&mut updated, l).await?;
+    }
+
+    let address = utils::write(&updated.to_string()?).await?;
+    indexes.set(head.branch(), head.version()?, address);
+    indexes.write().await?;
+    return Ok(updated);
+}
+
+/// Files a page in the taxonomy under its tags, replacing it if it is already there.
+async fn tag(taxonomy: &mut Taxonomy, location: Location) -> Result<(), String> {
+    let page = Page::from(&location.end()?).await?;
+    let tags = page.fields.get(taxonomy::FIELD).map(|t| taxonomy::tags(t)).unwrap_or_default();
+    taxonomy.insert(page.id(), page.title, tags);
+    return Ok(());
+}
+
+/// Brings the taxonomy of master up to date with its head.
+/// Only the pages that changed since it was last updated are read,
+/// unless it has never been built, or the version it was built from is gone.
+pub async fn retag() -> Result<Taxonomy, String> {
+    let head = head(Location::from_branch("master".to_owned())).await?;
+
+    let previous = match Taxonomy::current().await {
+        Some(t) if t.version == head.version()? => return Ok(t),
+        Some(t) => {
+            let before = Location::from_branch_and_version(head.branch(), t.version.to_owned());
+            touched(before, head.clone()).await.ok().map(|c| (t, c))
+        },
+        None => None,
+    };
+
+    let (mut taxonomy, (removed, changed)) = match previous {
+        Some(p) => p,
+        None    => (Taxonomy::default(), (vec![], descendants(root(head.clone())?).await?)),
+    };
+    for id in removed.iter() {
+        taxonomy.remove(id);
+    }
+    for l in changed.into_iter() {
+        tag(&mut taxonomy, l).await?;
+    }
+
+    taxonomy.version = head.version()?;
+    taxonomy.write().await?;
+    return Ok(taxonomy);
+}
+
+/// Searches the head of a branch, best matches first.
+pub async fn search(location: Location, query: &str) -> Result<Vec<Hit>, String> {
+    let index = reindex(location).await?;
+    return Ok(index.query(&tokenize(query)));
+}
+
+/// Pages on the head of master that mention every word in a query.
+/// The bloom filters stored in `Shorthand` rule out most pages without loading them.
+pub async fn mentioning(query: &str) -> Result<Vec<Location>, String> {
+    let terms = tokenize(query);
+    if terms.is_empty() {
+        return Err("Nothing to look for".to_owned());
+    }
+
+    // only the most recent filter for each page is current
+    let mut latest: HashMap<String, (usize, String)> = HashMap::new();
+    for (_name, (ver_no, id, bloom)) in Shorthand::read().await?.unwrap().into_iter() {
+        match latest.get(&id) {
+            Some((newest, _)) if *newest > ver_no => (),
+            _ => { latest.insert(id, (ver_no, bloom)); },
+        }
+    }
+
+    let head = head(Location::from_branch("master".to_owned())).await?;
+    let mut found = vec![];
+    for (id, (_, bloom)) in latest.into_iter() {
+        let filter = Bloom::parse(&bloom)?;
+        if !terms.iter().all(|t| filter.may_contain(t)) { continue; }
+
+        // the page may have been deleted since, or be a false positive
+        let location = match locate_id(head.clone(), id).await {
+            Ok(l)  => l,
+            Err(_) => continue,
+        };
+        let (title, content, _) = read(&location).await?;
+        let words = tokenize(&(title + " " + &content)).into_iter().collect::<HashSet<String>>();
+        if terms.iter().all(|t| words.contains(t)) {
+            found.push(location);
+        }
+    }
+
+    return Ok(found);
+}
+
+/// Restores the title, content and fields a page had at an earlier version
+/// by editing the same page on the head of the branch.
+pub async fn restore(old: Location, signature: Signature) -> Result<Location, String> {
+    let id      = old.id().await?;
+    let current = locate_id(head(old.clone()).await?, id).await
+        .map_err(|_| "This Page no longer exists on the head of the Branch".to_owned())?;
+
+    let (title, content, fields) = read(&old).await?;
+    edit(current.clone(), Some(title), Some(content), Some(fields), signature).await?;
+    return Ok(current);
+}
+
+/// Makes an earlier version the head of its branch again.
+/// The old root is appended as a new version, so no history is lost.
+pub async fn reset(version: Location, signature: Signature) -> Result<(), String> {
+    writable(&version.branch()).await?;
+    let head = head(version.clone()).await?;
+    if head.version()? == version.version()? {
+        return Ok(());
+    }
+
+    let ver_no = versions(version.clone()).await?.len();
+    publish(&version.branch(), &head.version()?, version.version()?, signature, None).await?;
+
+    if version.branch() == "master" {
+        for change in changes(head, version.clone()).await? {
+            if let Change::Removed(_) = change { continue; }
+            let page = Page::from(&change.location().end()?).await?;
+            Shorthand::update(page.short(), ver_no, page.id(), bloom(&page).await?).await?;
+        }
+    }
     return Ok(());
 }
 
+/// Names a version of a branch, so it can be found by name instead of number.
+/// Once made, a release always names the same version.
+pub async fn release(version: Location, name: String, author: String) -> Result<(), String> {
+    Releases::validate(&name)?;
+    let root = version.version()?;
+    if !Branch::from(&version.branch()).await?.live().any(|v| *v == root) {
+        return Err("Can only release a version that is on the Branch".to_owned());
+    }
+
+    let release = Release {
+        branch:    version.branch(),
+        version:   root,
+        author,
+        timestamp: utils::now()?,
+    };
+
+    let lease = Lease::acquire("releases").await?;
+    let mut releases = Releases::read().await?;
+    let result = match releases.insert(name, release) {
+        Ok(())  => releases.write().await,
+        Err(e)  => Err(e),
+    };
+    lease.release().await?;
+    return result;
+}
+
+/// The version of a branch a release names.
+pub async fn released(branch: &str, name: &str) -> Result<Location, String> {
+    let releases = Releases::read().await?;
+    let release  = releases.get(name)
+        .ok_or(format!("There is no Release named {}", name))?;
+    if release.branch != branch {
+        return Err(format!("Release {} is a version of {}, not {}", name, release.branch, branch));
+    }
+    return Ok(Location::from_branch_and_version(release.branch.to_owned(), release.version.to_owned()));
+}
+
 pub async fn read(location: &Location) -> Result<(String, String, HashMap<String, String>), String> {
     let page = Page::from(&location.end()?).await?;
 
@@ -229,37 +1011,1569 @@ pub async fn read(location: &Location) -> Result<(String, String, HashMap<String
     return Ok((title, content, fields));
 }
 
-pub async fn title(location: &Location) -> Result<String, String> {
-    let page = Page::from(&location.end()?).await?;
-    return Ok(page.title);
+/// Compares a page between two versions.
+/// A page missing from one side is compared against an empty page,
+/// and content with the same address on both sides is never read.
+pub async fn diff(before: Option<Location>, after: Option<Location>) -> Result<PageDiff, String> {
+    let old = match &before { Some(l) => Some(Page::from(&l.end()?).await?), None => None };
+    let new = match &after  { Some(l) => Some(Page::from(&l.end()?).await?), None => None };
+    if old.is_none() && new.is_none() {
+        return Err("Page does not exist on either version".to_owned());
+    }
+
+    let empty = HashMap::new();
+    let (old_title, new_title) = (
+        old.as_ref().map(|p| p.title.to_owned()).unwrap_or_default(),
+        new.as_ref().map(|p| p.title.to_owned()).unwrap_or_default(),
+    );
+    let fields = PageDiff::fields(
+        old.as_ref().map_or(&empty, |p| &p.fields),
+        new.as_ref().map_or(&empty, |p| &p.fields),
+    );
+
+    let mut children = vec![];
+    let none = Children::default();
+    let (old_children, new_children) = (
+        old.as_ref().map_or(&none, |p| &p.children),
+        new.as_ref().map_or(&none, |p| &p.children),
+    );
+    for (id, address) in new_children.iter() {
+        if !old_children.contains_key(id) { children.push((Page::from(address).await?.title, true)); }
+    }
+    for (id, address) in old_children.iter() {
+        if !new_children.contains_key(id) { children.push((Page::from(address).await?.title, false)); }
+    }
+
+    let (old_content, new_content) = (
+        old.as_ref().map(|p| p.content.to_owned()),
+        new.as_ref().map(|p| p.content.to_owned()),
+    );
+    let content = if old_content == new_content {
+        None
+    } else {
+        let before = match old_content { Some(c) => utils::read(&c).await?, None => "".to_owned() };
+        let after  = match new_content { Some(c) => utils::read(&c).await?, None => "".to_owned() };
+        Some(lines(&before, &after))
+    };
+
+    return Ok(PageDiff {
+        title: if old_title != new_title { Some((old_title, new_title)) } else { None },
+        fields,
+        children,
+        content,
+    });
 }
 
-// more than just a create and delete.
-// preserves id, commits to HRDB in safe order.
-pub async fn relocate(from: Location, to: Location) -> Result<(), String> {
-    let mut parent  = Page::from(&from.back()?.end()?).await?;
-    let from_page   = Page::from(&from.end()?).await?;
-    let mut to_page = Page::from(&to.end()?).await?;
+/// Every version of a branch in which a page's address changed, oldest first,
+/// with the page as it was in each, starting from the version it was added in.
+/// Versions that share a root with the one before are skipped without a read.
+pub async fn history(location: Location, id: String) -> Result<Vec<(usize, Location)>, String> {
+    let name   = location.branch();
+    let branch = Branch::from(&name).await?;
 
-    to_page.children.insert(from_page.id().clone(), from.end()?);
-    parent.children.remove(&from_page.id());
-    commit(to, to_page).await?;
-    commit(from.back()?, parent).await?;
+    let mut history = vec![];
+    let mut last: Option<String> = None; // address
+    let mut previous: Option<&String> = None; // root
+    for (ver_no, (root, paths)) in branch.versions.iter().zip(branch.paths.iter()).enumerate() {
+        if previous == Some(root) || root == PRUNED { continue; }
+        previous = Some(root);
 
-    return Ok(());
+        let found = match paths {
+            Some(p) => Paths::from(p).await?.get(&id)
+                .map(|path| Location::from_branch_version_and_path(name.to_owned(), root.to_owned(), path)),
+            None => locate_id(Location::from_branch_and_version(name.to_owned(), root.to_owned()), id.to_owned()).await.ok(),
+        };
+        let address = match &found {
+            Some(l) => Some(l.end()?),
+            None    => None,
+        };
+        if address != last {
+            if let Some(l) = found { history.push((ver_no, l)); }
+            last = address;
+        }
+    }
+    return Ok(history);
 }
 
-// looks like a simple read + write. too trivial to include.
-// pub async fn duplicate(location: Location) -> Result<(), String> {
-//     let (title, content, fields) = read(&location).await?;
-//     create(location.back()?, title, content, fields).await?;
-//     return Ok(());
-// }
+/// Each line of a page's content on the head of its branch,
+/// with the number of the version that last changed it.
+/// Only versions where the content's address changed are read and diffed.
+pub async fn blame(location: Location, id: String) -> Result<Vec<(String, usize)>, String> {
+    locate_id(head(location.clone()).await?, id.to_owned()).await
+        .map_err(|_| "This Page does not exist on the head of the Branch".to_owned())?;
 
-pub async fn delete(location: Location) -> Result<(), String> {
-    let mut parent = Page::from(&location.back()?.end()?).await?;
-    let page       = Page::from(&location.end()?).await?;
-    parent.children.remove(&page.id());
-    commit(location.back()?, parent).await?;
-    return Ok(());
+    let mut blamed  = vec![];
+    let mut content = None;
+    for (ver_no, l) in history(location, id).await? {
+        let page = Page::from(&l.end()?).await?;
+        if content.as_ref() == Some(&page.content) { continue; }
+        blamed  = diff::blame(blamed, &utils::read(&page.content).await?, ver_no);
+        content = Some(page.content);
+    }
+    return Ok(blamed);
+}
+
+pub async fn title(location: &Location) -> Result<String, String> {
+    let page = Page::from(&location.end()?).await?;
+    return Ok(page.title);
+}
+
+// more than just a create and delete.
+// preserves id, commits to HRDB in safe order.
+pub async fn relocate(from: Location, to: Location, signature: Signature) -> Result<(), String> {
+    if from.branch() != to.branch() {
+        return Err("Can only move a Page within the same Branch".to_owned());
+    }
+    writable(&to.branch()).await?;
+    on_head(&from).await?;
+    on_head(&to).await?;
+
+    // a page can not be nested under itself
+    let from_page = Page::from(&from.end()?).await?;
+    let to_ids    = to.ids().await?;
+    if to_ids.contains(&from_page.id()) {
+        return Err("Can not move a Page under itself or one of its descendants".to_owned());
+    }
+
+    let parent = from.back()?;
+    if parent.end()? == to.end()? {
+        return Ok(());
+    }
+
+    // remove the page from its parent
+    let mut old_parent = Page::from(&parent.end()?).await?;
+    old_parent.children.remove(&from_page.id());
+    let removed = rebuild(&parent, old_parent).await?;
+
+    // then find the destination again in the new tree, and insert it there
+    let intermediate = Location::from_branch_and_version(to.branch(), removed);
+    let destination  = locate(intermediate, to_ids).await?;
+    let mut to_page  = Page::from(&destination.end()?).await?;
+    to_page.children.insert(from_page.id(), from.end()?);
+    let root = rebuild(&destination, to_page).await?;
+
+    // both changes land in a single version
+    publish(&to.branch(), &to.version()?, root, signature, None).await?;
+    return Ok(());
+}
+
+/// Copies a page, and with `deep` every page beneath it, under another page,
+/// in a single version, returning the location of the copy.
+/// Copies get fresh ids but share their content with the originals,
+/// and leave out the slug field, which only one page may have.
+pub async fn duplicate(from: Location, to: Location, deep: bool, signature: Signature) -> Result<Location, String> {
+    writable(&to.branch()).await?;
+    on_head(&to).await?;
+
+    // parents before their children
+    let mut pages = vec![Page::from(&from.end()?).await?];
+    let mut i = 0;
+    while deep && i < pages.len() {
+        let children = pages[i].children.values().cloned().collect::<Vec<String>>();
+        for address in children.iter() {
+            pages.push(Page::from(address).await?);
+        }
+        i += 1;
+    }
+
+    // written children first, each copy nested under the copy of its parent
+    let mut copies: HashMap<String, (Page, String)> = HashMap::new(); // original id -> copy, address
+    for original in pages.iter().rev() {
+        let mut fields = original.fields.clone();
+        fields.remove(page::SLUG);
+        let mut copy = Page::new(original.title.to_owned(), original.content.to_owned(), fields);
+        for id in original.children.ids().iter() {
+            if let Some((child, address)) = copies.get(id) {
+                copy.children.insert(child.id(), address.to_owned());
+            }
+        }
+        let address = utils::write(&copy.to_string()?).await?;
+        copies.insert(original.id(), (copy, address));
+    }
+    let (top, address) = copies[&pages[0].id()].clone();
+
+    let mut parent = Page::from(&to.end()?).await?;
+    parent.children.insert(top.id(), address.to_owned());
+    let root = rebuild(&to, parent).await?;
+
+    let ver_no = versions(to.clone()).await?.len();
+    publish(&to.branch(), &to.version()?, root, signature, None).await?;
+
+    if to.branch() == "master" {
+        for original in pages.iter() {
+            let (copy, _) = &copies[&original.id()];
+            Shorthand::update(copy.short(), ver_no, copy.id(), bloom(copy).await?).await?;
+        }
+    }
+
+    let head = head(to).await?;
+    return locate_id(head, top.id()).await;
+}
+
+/// Moves a page one place earlier or later among its siblings.
+/// A page that is already first or last stays where it is.
+pub async fn shift(location: Location, up: bool, signature: Signature) -> Result<(), String> {
+    let parent   = location.back()?;
+    let mut page = Page::from(&parent.end()?).await?;
+    if !page.children.shift(&location.id().await?, up) {
+        return Ok(());
+    }
+    commit(parent, page, signature).await?;
+    return Ok(());
+}
+
+/// Sorts the children of a page by title or, given a field such as a date,
+/// by that field newest first. Children without the field come last, by title.
+pub async fn sort(location: Location, field: Option<String>, signature: Signature) -> Result<(), String> {
+    let mut page = Page::from(&location.end()?).await?;
+
+    let mut keys: HashMap<String, (bool, String, String)> = HashMap::new(); // id -> missing, field, title
+    for (id, address) in page.children.iter() {
+        let child = Page::from(address).await?;
+        let value = field.as_ref().and_then(|f| child.fields.get(f)).cloned();
+        keys.insert(id.to_owned(), (value.is_none(), value.unwrap_or_default(), child.title.to_lowercase()));
+    }
+
+    let before = page.children.ids();
+    match field {
+        None    => page.children.sort_by_key(|id| keys[id].2.to_owned()),
+        Some(_) => page.children.sort_by_key(|id| {
+            let (missing, value, title) = keys[id].to_owned();
+            (missing, std::cmp::Reverse(value), title)
+        }),
+    }
+    if page.children.ids() == before {
+        return Ok(());
+    }
+
+    commit(location, page, signature).await?;
+    return Ok(());
+}
+
+pub async fn delete(location: Location, signature: Signature) -> Result<(), String> {
+    let mut parent = Page::from(&location.back()?.end()?).await?;
+    let page       = Page::from(&location.end()?).await?;
+    parent.children.remove(&page.id());
+    commit(location.back()?, parent, signature).await?;
+    return Ok(());
+}
+
+// maintenance functions
+
+/// Deletes every object that can not be reached from a version on some branch,
+/// or from the search indexes and commits that belong to them.
+/// Objects are only deleted once a collection at least `gc::GRACE` earlier
+/// found them unreachable too, so changes still being made are left alone.
+/// With a retention period in days, versions committed before it are first dropped
+/// from the front of each branch; their numbers stay taken, but no longer resolve.
+/// The last version a branch shares with any other is kept, so they can still be merged.
+/// Reads and deletes stay within `fsck::READS`: if marking can not finish, nothing is swept,
+/// and objects past the budget are left for the next collection.
+/// A dry run changes nothing, and only reports what would be removed.
+pub async fn gc(retention: Option<u64>, dry_run: bool) -> Result<Report, String> {
+    // anything written after this is never swept
+    let stored = utils::keys().await?;
+    let now    = utils::now()?;
+
+    let names = utils::list("hrdb").await?;
+    let mut branches = vec![];
+    for name in names.iter() {
+        branches.push(Branch::from(name).await?);
+    }
+
+    // prune expired versions, keeping those shared between branches or released
+    let mut shared = HashSet::new();
+    for (i, a) in branches.iter().enumerate() {
+        for b in branches.iter().skip(i + 1) {
+            if let Ok(c) = a.common(b) { shared.insert(c); }
+        }
+    }
+    let releases = Releases::read().await?;
+    shared.extend(releases.iter().map(|(_, r)| r.version.to_owned()));
+
+    let mut reads  = 0;
+    let mut pruned = vec![];
+    if let Some(days) = retention {
+        let cutoff = now.saturating_sub(days * gc::DAY);
+        for (name, branch) in names.iter().zip(branches.iter_mut()) {
+            let count = gc::expired(branch, cutoff, &shared, &mut reads).await?;
+            if count == 0 { continue; }
+            branch.prune(branch.pruned() + count);
+            pruned.push((name.to_owned(), count));
+        }
+    }
+
+    // mark everything still reachable
+    let mut marked = HashSet::new();
+    for branch in branches.iter() {
+        for version in branch.live() {
+            gc::mark(version, &mut marked, &mut reads).await?;
+        }
+        marked.extend(branch.commits.iter().flatten().cloned());
+        marked.extend(branch.paths.iter().flatten().cloned());
+    }
+    let incomplete = reads >= fsck::READS;
+
+    let mut indexes = Indexes::read().await?;
+    indexes.retain(|branch, version| {
+        names.iter().zip(branches.iter())
+            .any(|(n, b)| n == branch && b.live().any(|v| v == version))
+    });
+    marked.extend(indexes.addresses());
+
+    // the taxonomy is rebuilt from scratch if the version it was built from is dropped
+    let mut outdated = None;
+    if let Ok(address) = utils::read("taxonomy").await {
+        let master = names.iter().position(|n| n == "master").map(|i| &branches[i]);
+        match Taxonomy::from(&address).await {
+            Ok(t) if master.is_some_and(|m| m.live().any(|v| *v == t.version)) => { marked.insert(address); },
+            _ => outdated = Some(address),
+        }
+    }
+
+    // sweep the rest, if it was unreachable long enough ago; only content addresses, never the lists
+    let mut unreachable = stored.into_iter()
+        .filter(|k| utils::is_address(k) && !marked.contains(k))
+        .collect::<Vec<String>>();
+    unreachable.sort();
+
+    if incomplete {
+        unreachable.clear();
+    }
+
+    // sweep in batches, leaving the rest known as garbage for the next collection
+    let mut garbage = gc::Garbage::read().await?;
+    let (mut swept, pending): (Vec<String>, Vec<String>) = unreachable.into_iter()
+        .partition(|a| garbage.since(a, now.saturating_sub(gc::GRACE)));
+    let deferred = swept.split_off(swept.len().min(fsck::READS.saturating_sub(reads)));
+
+    if !dry_run {
+        // versions may have been published since, so only drop the front
+        for (name, branch) in names.iter().zip(branches.iter()) {
+            if !pruned.iter().any(|(n, _)| n == name) { continue; }
+            let lease  = Lease::acquire(name).await?;
+            let result = match Branch::from(name).await {
+                Ok(mut current) => {
+                    current.prune(branch.pruned());
+                    utils::mutate(name, &current.lines().join("\n")).await
+                },
+                Err(e) => Err(e),
+            };
+            lease.release().await?;
+            result?;
+        }
+
+        // shorthands on dropped versions of master point at the oldest one left
+        let master = names.iter().position(|n| n == "master").map(|i| &branches[i]);
+        if let (Some(master), true) = (master, pruned.iter().any(|(name, _)| name == "master")) {
+            let oldest = master.pruned();
+            let table = Shorthand::read().await?.unwrap().into_iter()
+                .map(|(name, (ver_no, id, bloom))| (name, (ver_no.max(oldest), id, bloom)))
+                .collect::<HashMap<String, (usize, String, String)>>();
+            Shorthand::wrap(table).write().await?;
+        }
+
+        indexes.write().await?;
+        if outdated.is_some() {
+            utils::remove("taxonomy").await?;
+        }
+        for address in swept.iter() {
+            utils::remove(address).await?;
+        }
+        if !incomplete {
+            garbage.update(&[pending.as_slice(), deferred.as_slice()].concat(), now);
+            garbage.write().await?;
+        }
+    }
+
+    return Ok(Report {
+        dry_run,
+        reachable: marked.len(),
+        pruned,
+        swept,
+        pending:   pending.len(),
+        deferred:  deferred.len(),
+        incomplete,
+    });
+}
+
+/// Checks that every object hashes to its address, every reference resolves,
+/// every version's commit follows on from the one before it,
+/// and every shorthand points at a page that exists.
+/// Objects nothing refers to are reported too, though they are harmless.
+pub async fn fsck() -> Result<fsck::Report, String> {
+    let mut objects = Objects::load().await?;
+
+    for name in utils::list("hrdb").await?.iter() {
+        let lines = match utils::list(name).await {
+            Ok(l)  => l,
+            Err(_) => {
+                objects.problem("dangling", name, "Branch is listed, but has no versions".to_owned());
+                continue;
+            },
+        };
+
+        let mut previous: Option<String> = None;
+        for (ver_no, line) in lines.iter().enumerate() {
+            let (root, commit) = Commit::split(line);
+            if root == PRUNED {
+                previous = None;
+                continue;
+            }
+            let from = format!("version {} of {}", ver_no, name);
+            objects.tree(&root, &from);
+            if let Some(c) = &commit {
+                objects.commit(c, &root, previous.as_deref(), &from);
+            }
+            if let Some(p) = Paths::split(line) {
+                objects.paths(&p, &root, &from);
+            }
+            previous = commit;
+        }
+    }
+
+    for (branch, (version, address)) in Indexes::read().await?.iter() {
+        let from = format!("the search index of {}", branch);
+        objects.tree(version, &from);
+        objects.index(address, &from);
+    }
+
+    if let Ok(address) = utils::read("taxonomy").await {
+        objects.taxonomy(&address);
+    }
+
+    for (name, release) in Releases::read().await?.iter() {
+        let on_branch = match Branch::from(&release.branch).await {
+            Ok(b)  => b.versions.contains(&release.version),
+            Err(_) => false,
+        };
+        if !on_branch {
+            objects.problem("release", name, format!("Names version {} of {}, which is not on that Branch", release.version, release.branch));
+        }
+    }
+
+    // looked up in the Paths indexes already loaded where possible, to save reads
+    let master = versions(Location::from_branch("master".to_owned())).await?;
+    let indexed = Branch::from("master").await?.paths;
+    let mut shorthands = Shorthand::read().await?.unwrap().into_iter().collect::<Vec<_>>();
+    shorthands.sort_by(|a, b| a.0.cmp(&b.0));
+    for (name, (ver_no, id, _)) in shorthands.into_iter() {
+        let found = match master.get(ver_no) {
+            Some(version) => match indexed.get(ver_no).cloned().flatten().and_then(|p| objects.indexes(&p, &id)) {
+                Some(found) => found,
+                None        => locate_id(version.to_owned(), id.to_owned()).await.is_ok(),
+            },
+            None => false,
+        };
+        if !found {
+            objects.problem("shorthand", &name, format!("Points at Page {} on version {} of master, which does not exist", id, ver_no));
+        }
+    }
+
+    let table = Shorthand::read().await?;
+    for (name, id) in Aliases::read().await?.iter() {
+        if table.find(id).is_none() {
+            objects.problem("alias", name, format!("Redirects to Page {}, which has no Shorthand", id));
+        }
+    }
+
+    objects.unreachable();
+    return Ok(fsck::Report {
+        objects:  objects.checked(),
+        unread:   objects.unread(),
+        problems: objects.problems,
+    });
+}
+
+/// Bundles every branch, the shorthand table, and every object reachable from them.
+/// A bundle that would take more than `fsck::READS` reads to gather is refused,
+/// since one missing objects could not be imported anyway.
+pub async fn export() -> Result<Bundle, String> {
+    let mut branches = vec![];
+    let mut marked   = HashSet::new();
+    let mut reads    = 0;
+    for name in utils::list("hrdb").await?.into_iter() {
+        let branch = Branch::from(&name).await?;
+        for version in branch.live() {
+            gc::mark(version, &mut marked, &mut reads).await?;
+        }
+        marked.extend(branch.commits.iter().flatten().cloned());
+        marked.extend(branch.paths.iter().flatten().cloned());
+        branches.push((name, branch.lines()));
+    }
+    if reads + marked.len() > fsck::READS {
+        return Err(format!(
+            "Exporting would read more than {} objects, more than a request may, so nothing was exported",
+            fsck::READS,
+        ));
+    }
+
+    let mut objects = BTreeMap::new();
+    for address in marked.into_iter() {
+        let value = utils::read(&address).await?;
+        objects.insert(address, value);
+    }
+
+    return Ok(Bundle {
+        format:    bundle::FORMAT,
+        branches,
+        shorthand: Shorthand::read().await?.unwrap(),
+        aliases:   Aliases::read().await?,
+        protected: protected().await?,
+        releases:  Releases::read().await?,
+        objects,
+    });
+}
+
+/// Merges a bundle into the store, after checking every object in it.
+/// Branches that do not exist yet are created, and branches the bundle
+/// only adds versions to are fast-forwarded. A branch that has diverged
+/// from the bundle is left alone, though its objects are still imported.
+/// Shorthands are numbered by master's versions, so they are only taken
+/// from the bundle if master agrees with it, and releases are skipped
+/// unless the version they name is on their branch.
+pub async fn import(bundle: Bundle) -> Result<Imported, String> {
+    bundle.verify().await?;
+    for (name, _) in bundle.branches.iter() {
+        Branch::validate(name)?;
+    }
+
+    for value in bundle.objects.values() {
+        utils::write(value).await?;
+    }
+
+    utils::ensure("hrdb").await?;
+    let mut branches = vec![];
+    for (name, lines) in bundle.branches.iter() {
+        let lease   = Lease::acquire(name).await?;
+        let outcome = receive(name, lines).await;
+        lease.release().await?;
+        branches.push((name.to_owned(), outcome?));
+    }
+
+    for name in bundle.protected.iter() {
+        protect(Location::from_branch(name.to_owned()), true).await?;
+    }
+
+    // releases never change, so only new names are taken,
+    // and only if the version they name made it onto their branch
+    let lease   = Lease::acquire("releases").await?;
+    let skipped = adopt(&bundle.releases).await;
+    lease.release().await?;
+    let skipped = skipped?;
+
+    let master = branches.iter().find(|(name, _)| name == "master").map(|(_, o)| *o);
+    if master.is_some() && master != Some("diverged") {
+        let mut table = match Shorthand::read().await {
+            Ok(t)  => t.unwrap(),
+            Err(_) => HashMap::new(),
+        };
+        for (name, entry) in bundle.shorthand.into_iter() {
+            // the store's own entries are newer unless master moved forward
+            if master == Some("up to date") && table.contains_key(&name) { continue; }
+            table.insert(name, entry);
+        }
+        Shorthand::wrap(table).write().await?;
+
+        let mut aliases = Aliases::read().await?;
+        for (name, id) in bundle.aliases.iter() {
+            if master == Some("up to date") && aliases.get(name).is_some() { continue; }
+            aliases.insert(name.to_owned(), id.to_owned());
+        }
+        aliases.write().await?;
+    }
+
+    return Ok(Imported {
+        objects: bundle.objects.len(),
+        branches,
+        skipped,
+    });
+}
+
+/// Brings one branch in from a bundle, returning what was done to it.
+/// Only called while holding the branch's `Lease`.
+async fn receive(name: &str, lines: &[String]) -> Result<&'static str, String> {
+    return match utils::list(name).await {
+        Err(_) => {
+            utils::mutate(name, &lines.join("\n")).await?;
+            utils::push("hrdb", name.to_owned()).await?;
+            Ok("created")
+        },
+        Ok(existing) if lines.starts_with(&existing) && lines.len() > existing.len() => {
+            utils::mutate(name, &lines.join("\n")).await?;
+            Ok("fast-forwarded")
+        },
+        Ok(existing) if existing.starts_with(lines) => Ok("up to date"),
+        Ok(_) => Ok("diverged"),
+    };
+}
+
+/// Takes the releases a bundle names that the store doesn't have yet,
+/// returning the names of those skipped because their version isn't on their branch.
+/// Only called while holding the releases' `Lease`.
+async fn adopt(bundled: &Releases) -> Result<Vec<String>, String> {
+    let mut skipped  = vec![];
+    let mut releases = Releases::read().await?;
+    for (name, release) in bundled.iter() {
+        if releases.get(name).is_some() { continue; }
+        let on_branch = match Branch::from(&release.branch).await {
+            Ok(b)  => b.live().any(|v| *v == release.version),
+            Err(_) => false,
+        };
+        if on_branch {
+            releases.insert(name.to_owned(), release.clone())?;
+        } else {
+            skipped.push(name.to_owned());
+        }
+    }
+    releases.write().await?;
+    return Ok(skipped);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use std::collections::HashMap;
+    use futures::executor::block_on;
+    use crate::hrdb::store;
+    use super::*;
+
+    /// Runs against a fresh, initialized in-memory store.
+    fn run<F: std::future::Future<Output = ()>>(test: impl FnOnce() -> F) {
+        store::using(Rc::new(store::Memory::new()));
+        block_on(async {
+            init().await.unwrap();
+            test().await;
+        });
+    }
+
+    fn sig() -> Signature {
+        Signature::new("tester".to_owned(), None)
+    }
+
+    fn branch(name: &str) -> Location {
+        Location::from_branch(name.to_owned())
+    }
+
+    async fn fork_head(from: &str, into: &str) -> Result<(), String> {
+        let ver_no = versions(branch(from)).await.unwrap().len() - 1;
+        fork(head(branch(from)).await.unwrap(), ver_no, branch(into)).await
+    }
+
+    async fn home(name: &str) -> Location {
+        root(head(branch(name)).await.unwrap()).unwrap()
+    }
+
+    async fn at_head(name: &str, id: &str) -> Location {
+        locate_id(head(branch(name)).await.unwrap(), id.to_owned()).await.unwrap()
+    }
+
+    async fn add(parent: Location, title: &str, content: &str) -> String {
+        create(parent, title.to_owned(), content.to_owned(), HashMap::new(), sig()).await
+            .unwrap().id().await.unwrap()
+    }
+
+    async fn titles(location: Location) -> Vec<String> {
+        let mut titles = vec![];
+        for child in children(location).await.unwrap() {
+            titles.push(title(&child).await.unwrap());
+        }
+        return titles;
+    }
+
+    #[test]
+    fn create_adds_a_version_with_the_page() {
+        run(|| async {
+            let id = add(home("master").await, "Notes", "hello").await;
+
+            assert_eq!(versions(branch("master")).await.unwrap().len(), 2);
+            let (title, content, _) = read(&at_head("master", &id).await).await.unwrap();
+            assert_eq!((title.as_str(), content.as_str()), ("Notes", "hello"));
+            assert_eq!(titles(home("master").await).await, vec!["Notes"]);
+        });
+    }
+
+    #[test]
+    fn edit_changes_only_what_is_given() {
+        run(|| async {
+            let id = add(home("master").await, "Notes", "hello").await;
+            edit(at_head("master", &id).await, None, Some("goodbye".to_owned()), None, sig()).await.unwrap();
+
+            let (title, content, _) = read(&at_head("master", &id).await).await.unwrap();
+            assert_eq!((title.as_str(), content.as_str()), ("Notes", "goodbye"));
+
+            // the earlier version is untouched
+            let first = versions(branch("master")).await.unwrap()[1].clone();
+            let (_, old, _) = read(&locate_id(first, id).await.unwrap()).await.unwrap();
+            assert_eq!(old, "hello");
+        });
+    }
+
+    #[test]
+    fn delete_removes_the_page_and_its_children() {
+        run(|| async {
+            let id = add(home("master").await, "Notes", "").await;
+            let nested = add(at_head("master", &id).await, "Nested", "").await;
+            delete(at_head("master", &id).await, sig()).await.unwrap();
+
+            assert!(titles(home("master").await).await.is_empty());
+            assert!(locate_id(head(branch("master")).await.unwrap(), nested).await.is_err());
+        });
+    }
+
+    #[test]
+    fn relocate_moves_a_page_in_one_version() {
+        run(|| async {
+            let a = add(home("master").await, "A", "").await;
+            let b = add(home("master").await, "B", "").await;
+            let before = versions(branch("master")).await.unwrap().len();
+
+            relocate(at_head("master", &b).await, at_head("master", &a).await, sig()).await.unwrap();
+
+            assert_eq!(versions(branch("master")).await.unwrap().len(), before + 1);
+            assert_eq!(titles(home("master").await).await, vec!["A"]);
+            assert_eq!(titles(at_head("master", &a).await).await, vec!["B"]);
+
+            // not underneath itself
+            let moved = relocate(at_head("master", &a).await, at_head("master", &b).await, sig()).await;
+            assert!(moved.is_err());
+        });
+    }
+
+    fn content(body: &str) -> Option<String> {
+        Some(body.to_owned())
+    }
+
+    #[test]
+    fn merge_combines_changes_from_both_branches() {
+        run(|| async {
+            let a = add(home("master").await, "A", "a").await;
+            let b = add(home("master").await, "B", "b").await;
+            fork_head("master", "draft").await.unwrap();
+
+            edit(at_head("draft", &a).await, None, content("a1"), None, sig()).await.unwrap();
+            edit(at_head("master", &b).await, None, content("b1"), None, sig()).await.unwrap();
+
+            let conflicts = merge(head(branch("draft")).await.unwrap(), head(branch("master")).await.unwrap(), sig()).await.unwrap();
+            assert!(conflicts.is_empty());
+            assert_eq!(read(&at_head("master", &a).await).await.unwrap().1, "a1");
+            assert_eq!(read(&at_head("master", &b).await).await.unwrap().1, "b1");
+
+            // both sides changing the same page is a conflict
+            edit(at_head("draft", &b).await, None, content("b2"), None, sig()).await.unwrap();
+            edit(at_head("master", &b).await, None, content("b3"), None, sig()).await.unwrap();
+            let conflicts = merge(head(branch("draft")).await.unwrap(), head(branch("master")).await.unwrap(), sig()).await.unwrap();
+            assert_eq!(conflicts.len(), 1);
+            assert_eq!(conflicts[0].id, b);
+        });
+    }
+
+    #[test]
+    fn merge_again_starts_from_the_last_merge() {
+        run(|| async {
+            let a = add(home("master").await, "A", "a").await;
+            let b = add(home("master").await, "B", "b").await;
+            fork_head("master", "draft").await.unwrap();
+
+            edit(at_head("draft", &a).await, None, content("a1"), None, sig()).await.unwrap();
+            edit(at_head("master", &b).await, None, content("b1"), None, sig()).await.unwrap();
+            let conflicts = merge(head(branch("draft")).await.unwrap(), head(branch("master")).await.unwrap(), sig()).await.unwrap();
+            assert!(conflicts.is_empty());
+
+            edit(at_head("draft", &a).await, None, content("a2"), None, sig()).await.unwrap();
+            let conflicts = merge(head(branch("draft")).await.unwrap(), head(branch("master")).await.unwrap(), sig()).await.unwrap();
+            assert!(conflicts.is_empty());
+            assert_eq!(read(&at_head("master", &a).await).await.unwrap().1, "a2");
+
+            // and back the other way, through master's merge
+            let conflicts = merge(head(branch("master")).await.unwrap(), head(branch("draft")).await.unwrap(), sig()).await.unwrap();
+            assert!(conflicts.is_empty());
+            assert_eq!(read(&at_head("draft", &b).await).await.unwrap().1, "b1");
+        });
+    }
+
+    async fn found(name: &str, query: &str) -> Vec<String> {
+        let mut ids = search(branch(name), query).await.unwrap().into_iter()
+            .map(|hit| hit.id)
+            .collect::<Vec<String>>();
+        ids.sort();
+        return ids;
+    }
+
+    #[test]
+    fn search_follows_edits_and_deletes() {
+        run(|| async {
+            let a = add(home("master").await, "Apples", "red fruit").await;
+            let b = add(home("master").await, "Bananas", "yellow fruit").await;
+            let mut both = vec![a.clone(), b.clone()];
+            both.sort();
+            assert_eq!(found("master", "fruit").await, both);
+
+            // the index is brought up to date incrementally
+            edit(at_head("master", &a).await, None, content("green"), None, sig()).await.unwrap();
+            assert_eq!(found("master", "fruit").await, vec![b.clone()]);
+            assert_eq!(found("master", "green").await, vec![a.clone()]);
+
+            delete(at_head("master", &b).await, sig()).await.unwrap();
+            assert!(found("master", "fruit").await.is_empty());
+
+            let mentions = mentioning("green").await.unwrap();
+            assert_eq!(mentions.len(), 1);
+            assert_eq!(mentions[0].id().await.unwrap(), a);
+        });
+    }
+
+    #[test]
+    fn restore_and_reset_add_versions() {
+        run(|| async {
+            let a = add(home("master").await, "A", "one").await;
+            let old = versions(branch("master")).await.unwrap().last().unwrap().clone();
+            edit(at_head("master", &a).await, None, content("two"), None, sig()).await.unwrap();
+            let b = add(home("master").await, "B", "").await;
+
+            restore(locate_id(old.clone(), a.clone()).await.unwrap(), sig()).await.unwrap();
+            assert_eq!(read(&at_head("master", &a).await).await.unwrap().1, "one");
+            assert_eq!(titles(home("master").await).await, vec!["A", "B"]);
+
+            let before = versions(branch("master")).await.unwrap().len();
+            reset(old.clone(), sig()).await.unwrap();
+            let after = versions(branch("master")).await.unwrap();
+            assert_eq!(after.len(), before + 1);
+            assert_eq!(after.last().unwrap().version().unwrap(), old.version().unwrap());
+            assert!(locate_id(head(branch("master")).await.unwrap(), b).await.is_err());
+        });
+    }
+
+    /// Makes every object collection has seen unreachable look like it was found long ago.
+    async fn age_garbage() {
+        let garbage = gc::Garbage::read().await.unwrap();
+        let seen = garbage.iter().map(|(a, _)| a.to_owned()).collect::<Vec<String>>();
+        let mut aged = gc::Garbage::default();
+        aged.update(&seen, 0);
+        aged.write().await.unwrap();
+    }
+
+    #[test]
+    fn gc_sweeps_only_what_stayed_unreachable() {
+        run(|| async {
+            add(home("master").await, "A", "kept").await;
+            let orphan = utils::write("nothing refers to this").await.unwrap();
+
+            // the first collection only notices it
+            let report = gc(None, false).await.unwrap();
+            assert!(report.swept.is_empty());
+            assert_eq!(report.pending, 1);
+            assert!(utils::read(&orphan).await.is_ok());
+
+            // written since, so possibly part of a change still being made
+            let newer = utils::write("nor this").await.unwrap();
+            age_garbage().await;
+
+            let report = gc(None, true).await.unwrap();
+            assert_eq!(report.swept, vec![orphan.clone()]);
+            assert!(utils::read(&orphan).await.is_ok());
+
+            let report = gc(None, false).await.unwrap();
+            assert_eq!(report.swept, vec![orphan.clone()]);
+            assert!(utils::read(&orphan).await.is_err());
+            assert!(utils::read(&newer).await.is_ok());
+            assert_eq!(read(&home("master").await).await.unwrap().0, "Home");
+        });
+    }
+
+    #[test]
+    fn gc_keeps_the_numbers_of_pruned_versions() {
+        run(|| async {
+            let a = add(home("master").await, "A", "one").await;
+            edit(at_head("master", &a).await, None, content("two"), None, sig()).await.unwrap();
+            edit(at_head("master", &a).await, None, content("three"), None, sig()).await.unwrap();
+
+            // versions without a commit are as old as can be
+            let lines = utils::list("master").await.unwrap().iter()
+                .map(|l| Commit::split(l).0)
+                .collect::<Vec<String>>();
+            utils::mutate("master", &lines.join("\n")).await.unwrap();
+
+            let report = gc(Some(0), false).await.unwrap();
+            assert_eq!(report.pruned, vec![("master".to_owned(), 3)]);
+
+            let versions = versions(branch("master")).await.unwrap();
+            assert_eq!(versions.len(), 4);
+            assert!(versions[..3].iter().all(|v| v.version().unwrap() == PRUNED));
+            assert_eq!(read(&at_head("master", &a).await).await.unwrap().1, "three");
+            assert!(Shorthand::read().await.unwrap().unwrap().values().all(|(ver_no, _, _)| *ver_no == 3));
+
+            // pruning again drops nothing more, and history starts at what is left
+            let report = gc(Some(0), false).await.unwrap();
+            assert!(report.pruned.is_empty());
+            let history = history(branch("master"), a).await.unwrap();
+            assert_eq!(history.iter().map(|(ver_no, _)| *ver_no).collect::<Vec<usize>>(), vec![3]);
+        });
+    }
+
+    /// The kind of every problem found, besides unreachable objects that are `ignored`.
+    fn kinds(report: &fsck::Report, ignored: &[String]) -> Vec<&'static str> {
+        report.problems.iter()
+            .filter(|p| !(p.kind == "unreachable" && ignored.contains(&p.key)))
+            .map(|p| p.kind)
+            .collect()
+    }
+
+    #[test]
+    fn fsck_finds_corrupt_missing_and_unreachable_objects() {
+        run(|| async {
+            let a = add(home("master").await, "A", "one").await;
+            edit(at_head("master", &a).await, None, content("two"), None, sig()).await.unwrap();
+
+            // replaced search indexes are harmless, and only ever unreachable
+            let report = fsck().await.unwrap();
+            assert!(report.problems.iter().all(|p| p.kind == "unreachable"));
+            let replaced = report.problems.into_iter().map(|p| p.key).collect::<Vec<String>>();
+
+            let orphan = utils::write("nothing refers to this").await.unwrap();
+            let report = fsck().await.unwrap();
+            assert_eq!(kinds(&report, &replaced), vec!["unreachable"]);
+            assert_eq!(report.problems.iter().find(|p| !replaced.contains(&p.key)).unwrap().key, orphan);
+            utils::remove(&orphan).await.unwrap();
+
+            let page = at_head("master", &a).await.end().unwrap();
+            let content = Page::from(&page).await.unwrap().content;
+            utils::mutate(&content, "tampered").await.unwrap();
+            assert_eq!(kinds(&fsck().await.unwrap(), &replaced), vec!["corrupt"]);
+
+            utils::remove(&content).await.unwrap();
+            assert_eq!(kinds(&fsck().await.unwrap(), &replaced), vec!["dangling"]);
+        });
+    }
+
+    #[test]
+    fn fsck_says_when_it_could_not_read_everything() {
+        run(|| async {
+            for i in 0..fsck::READS {
+                utils::write(&format!("filler {}", i)).await.unwrap();
+            }
+            let report = fsck().await.unwrap();
+            assert_eq!(report.objects, fsck::READS);
+            assert!(report.unread > 0);
+            assert_eq!(kinds(&report, &[]), vec!["incomplete"]);
+        });
+    }
+
+    /// Moves on to a fresh, initialized in-memory store, as if on another site.
+    async fn elsewhere() {
+        store::using(Rc::new(store::Memory::new()));
+        init().await.unwrap();
+    }
+
+    #[test]
+    fn import_rebuilds_an_exported_site() {
+        run(|| async {
+            let a = add(home("master").await, "A", "one").await;
+            fork_head("master", "draft").await.unwrap();
+            let bundle = export().await.unwrap().to_string().unwrap();
+
+            elsewhere().await;
+            let imported = import(Bundle::from(&bundle).unwrap()).await.unwrap();
+            assert!(imported.branches.contains(&("draft".to_owned(), "created")));
+            assert_eq!(read(&at_head("draft", &a).await).await.unwrap().1, "one");
+        });
+    }
+
+    #[test]
+    fn import_checks_every_page_before_writing_anything() {
+        run(|| async {
+            let a = add(home("master").await, "A", "").await;
+            let nested = add(at_head("master", &a).await, "Nested", "deep down").await;
+            fork_head("master", "draft").await.unwrap();
+            let content = Page::from(&at_head("master", &nested).await.end().unwrap()).await.unwrap().content;
+            let mut bundle = export().await.unwrap();
+            bundle.objects.remove(&content);
+
+            elsewhere().await;
+            assert!(import(bundle).await.is_err());
+            assert!(utils::list("draft").await.is_err());
+        });
+    }
+
+    #[test]
+    fn import_skips_releases_on_diverged_branches() {
+        run(|| async {
+            let a = add(home("master").await, "A", "one").await;
+            fork_head("master", "draft").await.unwrap();
+            let before = export().await.unwrap().to_string().unwrap();
+            edit(at_head("draft", &a).await, None, content("two"), None, sig()).await.unwrap();
+            release(head(branch("draft")).await.unwrap(), "launch".to_owned(), "tester".to_owned()).await.unwrap();
+            let after = export().await.unwrap().to_string().unwrap();
+
+            // every site starts with its own master, so only draft is shared
+            elsewhere().await;
+            import(Bundle::from(&before).unwrap()).await.unwrap();
+            edit(at_head("draft", &a).await, None, content("three"), None, sig()).await.unwrap();
+
+            let imported = import(Bundle::from(&after).unwrap()).await.unwrap();
+            assert!(imported.branches.contains(&("draft".to_owned(), "diverged")));
+            assert_eq!(imported.skipped, vec!["launch"]);
+            assert!(Releases::read().await.unwrap().get("launch").is_none());
+        });
+    }
+
+    #[test]
+    fn fork_starts_a_branch_at_a_version() {
+        run(|| async {
+            let a = add(home("master").await, "A", "one").await;
+            let at = head(branch("master")).await.unwrap();
+            edit(at_head("master", &a).await, None, content("two"), None, sig()).await.unwrap();
+
+            fork(at.clone(), 1, branch("draft")).await.unwrap();
+            assert_eq!(versions(branch("draft")).await.unwrap().len(), 2);
+            assert_eq!(read(&at_head("draft", &a).await).await.unwrap().1, "one");
+
+            edit(at_head("draft", &a).await, None, content("three"), None, sig()).await.unwrap();
+            assert_eq!(read(&at_head("master", &a).await).await.unwrap().1, "two");
+
+            assert!(fork(at.clone(), 1, branch("draft")).await.is_err());
+            assert!(fork(at.clone(), 1, branch("shorthand")).await.is_err());
+            assert!(fork(at.clone(), 1, branch("Not A Name")).await.is_err());
+            assert!(fork(at, 2, branch("elsewhere")).await.is_err());
+        });
+    }
+
+    #[test]
+    fn fork_after_a_reset_keeps_every_version() {
+        run(|| async {
+            let a = add(home("master").await, "A", "one").await;
+            let old = head(branch("master")).await.unwrap();
+            edit(at_head("master", &a).await, None, content("two"), None, sig()).await.unwrap();
+            reset(old, sig()).await.unwrap();
+
+            fork_head("master", "draft").await.unwrap();
+            assert_eq!(versions(branch("draft")).await.unwrap().len(), 4);
+        });
+    }
+
+    /// What changed between two versions, as verb and title, in a stable order.
+    async fn described(before: Location, after: Location) -> Vec<String> {
+        let mut described = vec![];
+        for change in changes(before, after).await.unwrap() {
+            described.push(format!("{} {}", change.verb(), title(change.location()).await.unwrap()));
+        }
+        described.sort();
+        return described;
+    }
+
+    #[test]
+    fn changes_between_versions_are_described() {
+        run(|| async {
+            let a = add(home("master").await, "A", "").await;
+            let b = add(home("master").await, "B", "").await;
+            let c = add(home("master").await, "C", "").await;
+            let before = head(branch("master")).await.unwrap();
+
+            edit(at_head("master", &a).await, None, content("new"), None, sig()).await.unwrap();
+            relocate(at_head("master", &b).await, at_head("master", &a).await, sig()).await.unwrap();
+            delete(at_head("master", &c).await, sig()).await.unwrap();
+            add(home("master").await, "D", "").await;
+
+            let after = head(branch("master")).await.unwrap();
+            assert_eq!(described(before, after).await, vec!["Added D", "Edited A", "Moved B", "Removed C"]);
+        });
+    }
+
+    #[test]
+    fn mentioning_needs_every_word_on_the_head() {
+        run(|| async {
+            let a = add(home("master").await, "Apples", "crisp and red").await;
+            add(home("master").await, "Cherries", "red and sweet").await;
+
+            let found = mentioning("crisp red").await.unwrap();
+            assert_eq!(found.len(), 1);
+            assert_eq!(found[0].id().await.unwrap(), a);
+            assert_eq!(mentioning("red").await.unwrap().len(), 2);
+
+            delete(at_head("master", &a).await, sig()).await.unwrap();
+            assert!(mentioning("crisp").await.unwrap().is_empty());
+            assert!(mentioning("").await.is_err());
+        });
+    }
+
+    #[test]
+    fn migrate_leaves_pruned_versions_alone() {
+        run(|| async {
+            let a = add(home("master").await, "A", "one").await;
+            edit(at_head("master", &a).await, None, content("two"), None, sig()).await.unwrap();
+            let lines = utils::list("master").await.unwrap().iter()
+                .map(|l| Commit::split(l).0)
+                .collect::<Vec<String>>();
+            utils::mutate("master", &lines.join("\n")).await.unwrap();
+            gc(Some(0), false).await.unwrap();
+
+            migrate().await.unwrap();
+            let commits = commits(branch("master")).await.unwrap();
+            assert_eq!(commits.iter().map(|c| c.is_some()).collect::<Vec<bool>>(), vec![false, false, true]);
+            assert_eq!(read(&at_head("master", &a).await).await.unwrap().1, "two");
+        });
+    }
+
+    #[test]
+    fn every_version_records_who_made_it_and_why() {
+        run(|| async {
+            let a = add(home("master").await, "A", "one").await;
+            edit(at_head("master", &a).await, None, content("two"), None,
+                Signature::new("someone".to_owned(), Some("Fixed a typo".to_owned()))).await.unwrap();
+
+            let commits = commits(branch("master")).await.unwrap();
+            let last = Commit::from(commits[2].as_ref().unwrap()).await.unwrap();
+            assert_eq!(last.author, "someone");
+            assert_eq!(last.message.as_deref(), Some("Fixed a typo"));
+            assert_eq!(last.root, head(branch("master")).await.unwrap().version().unwrap());
+            assert_eq!(last.parent, commits[1]);
+            assert!(last.timestamp > 0);
+        });
+    }
+
+    #[test]
+    fn migrate_backfills_commits() {
+        run(|| async {
+            add(home("master").await, "A", "one").await;
+            let lines = utils::list("master").await.unwrap().iter()
+                .map(|l| Commit::split(l).0)
+                .collect::<Vec<String>>();
+            utils::mutate("master", &lines.join("\n")).await.unwrap();
+
+            migrate().await.unwrap();
+            let commits = commits(branch("master")).await.unwrap();
+            let first  = Commit::from(commits[0].as_ref().unwrap()).await.unwrap();
+            let second = Commit::from(commits[1].as_ref().unwrap()).await.unwrap();
+            assert_eq!((first.timestamp, first.author.as_str()), (0, "unknown"));
+            assert_eq!(second.parent, commits[0]);
+        });
+    }
+
+    #[test]
+    fn diff_compares_a_page_between_versions() {
+        run(|| async {
+            let a = add(home("master").await, "A", "one\ntwo").await;
+            let before = at_head("master", &a).await;
+            add(at_head("master", &a).await, "Nested", "").await;
+            edit(at_head("master", &a).await, Some("B".to_owned()), content("one\nthree"), None, sig()).await.unwrap();
+
+            let changed = diff(Some(before.clone()), Some(at_head("master", &a).await)).await.unwrap();
+            assert_eq!(changed.title, Some(("A".to_owned(), "B".to_owned())));
+            assert_eq!(changed.children, vec![("Nested".to_owned(), true)]);
+            let kinds = changed.content.unwrap().iter().map(|l| l.kind).collect::<Vec<&str>>();
+            assert_eq!(kinds, vec!["same", "removed", "added"]);
+
+            assert!(diff(Some(before.clone()), Some(before)).await.unwrap().is_empty());
+        });
+    }
+
+    #[test]
+    fn changes_to_an_old_head_are_refused() {
+        run(|| async {
+            let a = add(home("master").await, "A", "one").await;
+            let stale = at_head("master", &a).await;
+            edit(at_head("master", &a).await, None, content("two"), None, sig()).await.unwrap();
+
+            let before = versions(branch("master")).await.unwrap().len();
+            let refused = edit(stale, None, content("three"), None, sig()).await;
+            assert_eq!(refused.err().as_deref(), Some(STALE));
+            assert_eq!(versions(branch("master")).await.unwrap().len(), before);
+            assert_eq!(read(&at_head("master", &a).await).await.unwrap().1, "two");
+        });
+    }
+
+    #[test]
+    fn changes_wait_for_the_lease() {
+        run(|| async {
+            let a = add(home("master").await, "A", "one").await;
+            let lease = Lease::acquire("master").await.unwrap();
+            assert!(Lease::acquire("master").await.is_err());
+            assert!(edit(at_head("master", &a).await, None, content("two"), None, sig()).await.is_err());
+
+            lease.release().await.unwrap();
+            edit(at_head("master", &a).await, None, content("two"), None, sig()).await.unwrap();
+            assert_eq!(read(&at_head("master", &a).await).await.unwrap().1, "two");
+        });
+    }
+
+    #[test]
+    fn paths_index_agrees_with_searching_the_tree() {
+        run(|| async {
+            let a = add(home("master").await, "A", "").await;
+            let b = add(at_head("master", &a).await, "B", "").await;
+            let c = add(at_head("master", &b).await, "C", "").await;
+            relocate(at_head("master", &c).await, at_head("master", &a).await, sig()).await.unwrap();
+
+            let mut indexed = vec![];
+            for id in [&a, &b, &c].iter() {
+                indexed.push(at_head("master", id).await.path().unwrap());
+            }
+
+            // without the index, every page is searched for
+            let lines = utils::list("master").await.unwrap().iter()
+                .map(|l| { let (root, commit) = Commit::split(l); format!("{} {}", root, commit.unwrap()) })
+                .collect::<Vec<String>>();
+            utils::mutate("master", &lines.join("\n")).await.unwrap();
+            for (id, path) in [&a, &b, &c].iter().zip(indexed.iter()) {
+                assert_eq!(&at_head("master", id).await.path().unwrap(), path);
+            }
+            assert_eq!(indexed[2].len(), 3);
+        });
+    }
+
+    fn fields(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn children_keep_the_order_they_are_given() {
+        run(|| async {
+            add(home("master").await, "cherry", "").await;
+            let b = add(home("master").await, "Banana", "").await;
+            create(home("master").await, "Apple".to_owned(), "".to_owned(), fields(&[("date", "2020-01-01")]), sig()).await.unwrap();
+            create(home("master").await, "Date".to_owned(), "".to_owned(), fields(&[("date", "2021-01-01")]), sig()).await.unwrap();
+            assert_eq!(titles(home("master").await).await, vec!["cherry", "Banana", "Apple", "Date"]);
+
+            shift(at_head("master", &b).await, true, sig()).await.unwrap();
+            assert_eq!(titles(home("master").await).await, vec!["Banana", "cherry", "Apple", "Date"]);
+
+            // already first, so nothing is committed
+            let before = versions(branch("master")).await.unwrap().len();
+            shift(at_head("master", &b).await, true, sig()).await.unwrap();
+            assert_eq!(versions(branch("master")).await.unwrap().len(), before);
+
+            sort(home("master").await, None, sig()).await.unwrap();
+            assert_eq!(titles(home("master").await).await, vec!["Apple", "Banana", "cherry", "Date"]);
+
+            // newest first, then those without the field by title
+            sort(home("master").await, Some("date".to_owned()), sig()).await.unwrap();
+            assert_eq!(titles(home("master").await).await, vec!["Date", "Apple", "Banana", "cherry"]);
+        });
+    }
+
+    #[test]
+    fn fields_are_kept_with_the_page() {
+        run(|| async {
+            let id = create(home("master").await, "A".to_owned(), "".to_owned(), fields(&[("tags", "[a]")]), sig()).await
+                .unwrap().id().await.unwrap();
+
+            // leaving fields out keeps them
+            edit(at_head("master", &id).await, None, content("body"), None, sig()).await.unwrap();
+            assert_eq!(read(&at_head("master", &id).await).await.unwrap().2, fields(&[("tags", "[a]")]));
+
+            edit(at_head("master", &id).await, None, None, Some(fields(&[("date", "2020-07-04")])), sig()).await.unwrap();
+            assert_eq!(read(&at_head("master", &id).await).await.unwrap().2, fields(&[("date", "2020-07-04")]));
+        });
+    }
+
+    async fn tagged(tag: &str) -> Vec<String> {
+        retag().await.unwrap().pages(tag).into_iter().map(|(_, title)| title).collect()
+    }
+
+    #[test]
+    fn taxonomy_follows_tags_on_master() {
+        run(|| async {
+            let a = create(home("master").await, "A".to_owned(), "".to_owned(), fields(&[("tags", "[fruit, red]")]), sig()).await
+                .unwrap().id().await.unwrap();
+            let b = add(home("master").await, "B", "").await;
+            create(at_head("master", &b).await, "C".to_owned(), "".to_owned(), fields(&[("tags", "[fruit]")]), sig()).await.unwrap();
+            assert_eq!(tagged("fruit").await, vec!["A", "C"]);
+
+            edit(at_head("master", &a).await, Some("Apple".to_owned()), None, Some(fields(&[("tags", "[red]")])), sig()).await.unwrap();
+            assert_eq!(tagged("fruit").await, vec!["C"]);
+            assert_eq!(tagged("red").await, vec!["Apple"]);
+
+            // moving a page keeps it, deleting one drops it with everything beneath
+            relocate(at_head("master", &b).await, at_head("master", &a).await, sig()).await.unwrap();
+            assert_eq!(tagged("fruit").await, vec!["C"]);
+            delete(at_head("master", &a).await, sig()).await.unwrap();
+            assert!(retag().await.unwrap().tags().is_empty());
+
+            // drafts are not tagged
+            fork_head("master", "draft").await.unwrap();
+            create(home("draft").await, "D".to_owned(), "".to_owned(), fields(&[("tags", "[fruit]")]), sig()).await.unwrap();
+            assert!(tagged("fruit").await.is_empty());
+        });
+    }
+
+    async fn names() -> Vec<String> {
+        branches().await.unwrap().into_iter().map(|l| l.branch()).collect()
+    }
+
+    #[test]
+    fn branches_can_be_protected_renamed_and_deleted() {
+        run(|| async {
+            let a = add(home("master").await, "A", "one").await;
+            fork_head("master", "draft").await.unwrap();
+            fork_head("master", "spare").await.unwrap();
+
+            protect(branch("draft"), true).await.unwrap();
+            assert!(edit(at_head("draft", &a).await, None, content("two"), None, sig()).await.is_err());
+            assert!(delete_branch(branch("draft")).await.is_err());
+            assert!(rename_branch(branch("draft"), branch("other")).await.is_err());
+            protect(branch("draft"), false).await.unwrap();
+            edit(at_head("draft", &a).await, None, content("two"), None, sig()).await.unwrap();
+
+            release(head(branch("draft")).await.unwrap(), "launch".to_owned(), "tester".to_owned()).await.unwrap();
+            rename_branch(branch("draft"), branch("final")).await.unwrap();
+            assert_eq!(names().await, vec!["master", "final", "spare"]);
+            assert_eq!(read(&at_head("final", &a).await).await.unwrap().1, "two");
+            assert_eq!(released("final", "launch").await.unwrap().version().unwrap(), head(branch("final")).await.unwrap().version().unwrap());
+
+            // released and master branches stay
+            assert!(delete_branch(branch("final")).await.is_err());
+            assert!(delete_branch(branch("master")).await.is_err());
+            assert!(rename_branch(branch("spare"), branch("final")).await.is_err());
+
+            delete_branch(branch("spare")).await.unwrap();
+            assert_eq!(names().await, vec!["master", "final"]);
+            assert!(utils::list("spare").await.is_err());
+            assert!(Lease::acquire("spare").await.is_ok());
+        });
+    }
+
+    #[test]
+    fn releases_name_a_version_for_good() {
+        run(|| async {
+            let a = add(home("master").await, "A", "one").await;
+            let first = head(branch("master")).await.unwrap();
+            release(first.clone(), "launch".to_owned(), "tester".to_owned()).await.unwrap();
+            edit(at_head("master", &a).await, None, content("two"), None, sig()).await.unwrap();
+
+            let named = released("master", "launch").await.unwrap();
+            assert_eq!(named.version().unwrap(), first.version().unwrap());
+            assert_eq!(read(&locate_id(named, a.clone()).await.unwrap()).await.unwrap().1, "one");
+
+            assert!(release(head(branch("master")).await.unwrap(), "launch".to_owned(), "tester".to_owned()).await.is_err());
+            for name in ["head", "12", "Not A Name", ""].iter() {
+                assert!(release(first.clone(), name.to_string(), "tester".to_owned()).await.is_err());
+            }
+            assert!(released("master", "missing").await.is_err());
+
+            // collection never drops a released version
+            let lines = utils::list("master").await.unwrap().iter()
+                .map(|l| Commit::split(l).0)
+                .collect::<Vec<String>>();
+            utils::mutate("master", &lines.join("\n")).await.unwrap();
+            gc(Some(0), false).await.unwrap();
+            assert_eq!(versions(branch("master")).await.unwrap()[0].version().unwrap(), PRUNED);
+            assert!(released("master", "launch").await.is_ok());
+        });
+    }
+
+    #[test]
+    fn cherry_pick_carries_one_change_over() {
+        run(|| async {
+            let a = add(home("master").await, "A", "one").await;
+            let b = add(home("master").await, "B", "one").await;
+            fork_head("master", "draft").await.unwrap();
+
+            let before = head(branch("draft")).await.unwrap();
+            edit(at_head("draft", &a).await, Some("Apple".to_owned()), content("two"), None, sig()).await.unwrap();
+            edit(at_head("draft", &b).await, None, content("two"), None, sig()).await.unwrap();
+
+            // only the one page comes over
+            let conflicts = cherry_pick(at_head("draft", &a).await, before.clone(), branch("master"), sig()).await.unwrap();
+            assert!(conflicts.is_empty());
+            assert_eq!(read(&at_head("master", &a).await).await.unwrap(), ("Apple".to_owned(), "two".to_owned(), HashMap::new()));
+            assert_eq!(read(&at_head("master", &b).await).await.unwrap().1, "one");
+
+            // unless master changed it too
+            edit(at_head("master", &b).await, None, content("three"), None, sig()).await.unwrap();
+            let conflicts = cherry_pick(at_head("draft", &b).await, before, branch("master"), sig()).await.unwrap();
+            assert_eq!(conflicts.len(), 1);
+            assert_eq!(read(&at_head("master", &b).await).await.unwrap().1, "three");
+        });
+    }
+
+    #[test]
+    fn history_and_blame_follow_only_versions_that_change_the_page() {
+        run(|| async {
+            let id = add(home("master").await, "Notes", "one\ntwo").await;
+            add(home("master").await, "Other", "").await;
+            edit(at_head("master", &id).await, None, content("one\nthree"), None, sig()).await.unwrap();
+
+            let changed = history(branch("master"), id.to_owned()).await.unwrap();
+            assert_eq!(changed.iter().map(|(v, _)| *v).collect::<Vec<usize>>(), vec![1, 3]);
+
+            let blamed = blame(branch("master"), id.to_owned()).await.unwrap();
+            assert_eq!(blamed, vec![("one".to_owned(), 1), ("three".to_owned(), 3)]);
+
+            // not on the branch at all
+            assert!(blame(branch("master"), "missing".to_owned()).await.is_err());
+        });
+    }
+
+    #[test]
+    fn renames_on_master_leave_an_alias_and_others_are_ignored() {
+        run(|| async {
+            let id = add(home("master").await, "Notes", "").await;
+            fork_head("master", "draft").await.unwrap();
+
+            edit(at_head("draft", &id).await, Some("Jottings".to_owned()), None, None, sig()).await.unwrap();
+            let table = Shorthand::read().await.unwrap();
+            assert_eq!(table.find(&id), Some("notes".to_owned()));
+            assert!(!table.unwrap().contains_key("jottings"));
+            assert_eq!(Aliases::read().await.unwrap().get("notes"), None);
+
+            edit(at_head("master", &id).await, Some("Diary".to_owned()), None, None, sig()).await.unwrap();
+            let table = Shorthand::read().await.unwrap();
+            assert_eq!(table.find(&id), Some("diary".to_owned()));
+            assert!(!table.unwrap().contains_key("notes"));
+            assert_eq!(Aliases::read().await.unwrap().get("notes"), Some(&id));
+
+            // another page taking the old name doesn't take over the alias
+            let other = add(home("master").await, "Notes", "").await;
+            assert_eq!(Shorthand::read().await.unwrap().find(&other), Some("notes-2".to_owned()));
+        });
+    }
+
+    #[test]
+    fn pages_are_not_named_after_routes() {
+        run(|| async {
+            let slugged = create(home("master").await, "Tidy".to_owned(), "".to_owned(), fields(&[("slug", "gc")]), sig()).await;
+            assert!(slugged.is_err());
+
+            let id = add(home("master").await, "Search", "").await;
+            assert_eq!(Shorthand::read().await.unwrap().find(&id), Some("search-2".to_owned()));
+        });
+    }
+
+    #[test]
+    fn duplicate_copies_a_page_and_with_deep_its_children() {
+        run(|| async {
+            let a = create(home("master").await, "A".to_owned(), "a".to_owned(), fields(&[("slug", "first")]), sig()).await
+                .unwrap().id().await.unwrap();
+            let b = add(at_head("master", &a).await, "B", "b").await;
+            let c = add(home("master").await, "C", "").await;
+            let before = versions(branch("master")).await.unwrap().len();
+
+            let shallow = duplicate(at_head("master", &a).await, at_head("master", &c).await, false, sig()).await.unwrap();
+            assert_eq!(versions(branch("master")).await.unwrap().len(), before + 1);
+            let (title, content, copied) = read(&shallow).await.unwrap();
+            assert_eq!((title.as_str(), content.as_str()), ("A", "a"));
+            assert!(!copied.contains_key("slug"));
+            assert_ne!(shallow.id().await.unwrap(), a);
+            assert!(titles(shallow).await.is_empty());
+
+            let deep = duplicate(at_head("master", &a).await, at_head("master", &c).await, true, sig()).await.unwrap();
+            assert_eq!(versions(branch("master")).await.unwrap().len(), before + 2);
+            assert_eq!(titles(at_head("master", &c).await).await, vec!["A", "A"]);
+            let nested = children(deep).await.unwrap();
+            assert_eq!(nested.len(), 1);
+            assert_ne!(nested[0].id().await.unwrap(), b);
+            let (_, content, _) = read(&nested[0]).await.unwrap();
+            assert_eq!(content, "b");
+
+            // the originals are untouched, and the copies have names of their own
+            assert_eq!(titles(at_head("master", &a).await).await, vec!["B"]);
+            let table = Shorthand::read().await.unwrap();
+            assert_eq!(table.find(&a), Some("first".to_owned()));
+            assert_eq!(table.find(&nested[0].id().await.unwrap()), Some("b-2".to_owned()));
+        });
+    }
+
+    #[test]
+    fn merge_carries_a_reorder_and_flags_two() {
+        run(|| async {
+            add(home("master").await, "A", "").await;
+            let b = add(home("master").await, "B", "").await;
+            let c = add(home("master").await, "C", "").await;
+            fork_head("master", "draft").await.unwrap();
+            fork_head("master", "other").await.unwrap();
+
+            shift(at_head("draft", &b).await, true, sig()).await.unwrap();
+            let conflicts = merge(head(branch("draft")).await.unwrap(), head(branch("master")).await.unwrap(), sig()).await.unwrap();
+            assert!(conflicts.is_empty());
+            assert_eq!(titles(home("master").await).await, vec!["B", "A", "C"]);
+
+            // reordered differently on both sides
+            shift(at_head("other", &c).await, true, sig()).await.unwrap();
+            let conflicts = merge(head(branch("other")).await.unwrap(), head(branch("master")).await.unwrap(), sig()).await.unwrap();
+            assert_eq!(conflicts.len(), 1);
+            assert_eq!(titles(home("master").await).await, vec!["B", "A", "C"]);
+        });
+    }
+
+    #[test]
+    fn merge_conflicts_commits_nothing() {
+        run(|| async {
+            let a = add(home("master").await, "A", "a").await;
+            fork_head("master", "draft").await.unwrap();
+            edit(at_head("draft", &a).await, None, content("a1"), None, sig()).await.unwrap();
+            let before = versions(branch("master")).await.unwrap().len();
+
+            assert!(merge_conflicts(branch("draft"), branch("master")).await.unwrap().is_empty());
+            assert_eq!(versions(branch("master")).await.unwrap().len(), before);
+
+            edit(at_head("master", &a).await, None, content("a2"), None, sig()).await.unwrap();
+            assert_eq!(merge_conflicts(branch("draft"), branch("master")).await.unwrap().len(), 1);
+        });
+    }
+
+    #[test]
+    fn init_migrates_only_once() {
+        run(|| async {
+            add(home("master").await, "A", "one").await;
+            let lines = utils::list("master").await.unwrap().iter()
+                .map(|l| Commit::split(l).0)
+                .collect::<Vec<String>>();
+            utils::mutate("master", &lines.join("\n")).await.unwrap();
+
+            init().await.unwrap();
+            assert!(commits(branch("master")).await.unwrap().iter().all(|c| c.is_none()));
+
+            utils::remove(MIGRATED).await.unwrap();
+            init().await.unwrap();
+            assert!(commits(branch("master")).await.unwrap().iter().all(|c| c.is_some()));
+            assert_eq!(utils::read(MIGRATED).await.unwrap(), MIGRATION);
+        });
+    }
+
+    #[test]
+    fn gc_sweeps_in_batches() {
+        run(|| async {
+            for i in 0..fsck::READS {
+                utils::write(&format!("filler {}", i)).await.unwrap();
+            }
+            gc(None, false).await.unwrap();
+            age_garbage().await;
+
+            let first = gc(None, false).await.unwrap();
+            assert!(!first.incomplete);
+            assert!(first.deferred > 0);
+            assert!(first.swept.len() + first.deferred >= fsck::READS);
+
+            // what was left over is still known, and goes next time
+            let second = gc(None, false).await.unwrap();
+            assert_eq!(second.swept.len(), first.deferred);
+            assert_eq!(second.deferred, 0);
+        });
+    }
+
+    #[test]
+    fn export_refuses_more_than_a_request_may_read() {
+        run(|| async {
+            let mut root = Page::from(&head(branch("master")).await.unwrap().version().unwrap()).await.unwrap();
+            for i in 0..fsck::READS {
+                let child = Page::new(format!("Page {}", i), root.content.to_owned(), HashMap::new());
+                root.children.insert(child.id(), utils::write(&child.to_string().unwrap()).await.unwrap());
+            }
+            let address = utils::write(&root.to_string().unwrap()).await.unwrap();
+            utils::push("master", address).await.unwrap();
+
+            assert!(export().await.is_err());
+        });
+    }
+
+    #[test]
+    fn a_change_lands_even_if_the_search_index_can_not_be_updated() {
+        run(|| async {
+            let a = add(home("master").await, "A", "one").await;
+            utils::mutate("search", "not an index").await.unwrap();
+
+            edit(at_head("master", &a).await, None, content("two"), None, sig()).await.unwrap();
+            assert_eq!(read(&at_head("master", &a).await).await.unwrap().1, "two");
+        });
+    }
 }
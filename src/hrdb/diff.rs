@@ -0,0 +1,323 @@
+use std::collections::HashMap;
+
+/// A single step in turning one sequence into another.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Edit<T> {
+    Same(T),
+    Added(T),
+    Removed(T),
+}
+
+/// The shortest edit script between two sequences.
+/// Uses the linear space refinement of Myers' algorithm, so memory stays
+/// proportional to the length of the sequences rather than their product.
+pub fn diff<T: PartialEq + Clone>(before: &[T], after: &[T]) -> Vec<Edit<T>> {
+    let mut edits = vec![];
+    script(before, after, &mut edits);
+    return edits;
+}
+
+/// Appends the edits turning `before` into `after`,
+/// splitting around the middle of the shortest path until one side is empty.
+fn script<T: PartialEq + Clone>(before: &[T], after: &[T], edits: &mut Vec<Edit<T>>) {
+    // trim the common prefix and suffix, usually most of a page
+    let prefix = before.iter().zip(after.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = before[prefix..].iter().rev().zip(after[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &before[prefix..before.len() - suffix];
+    let b = &after[prefix..after.len() - suffix];
+
+    edits.extend(before[..prefix].iter().cloned().map(Edit::Same));
+    if a.is_empty() || b.is_empty() {
+        edits.extend(a.iter().cloned().map(Edit::Removed));
+        edits.extend(b.iter().cloned().map(Edit::Added));
+    } else {
+        match middle(a, b) {
+            Some((x, y)) => {
+                script(&a[..x], &b[..y], edits);
+                script(&a[x..], &b[y..], edits);
+            },
+            None => {
+                edits.extend(a.iter().cloned().map(Edit::Removed));
+                edits.extend(b.iter().cloned().map(Edit::Added));
+            },
+        }
+    }
+    edits.extend(before[before.len() - suffix..].iter().cloned().map(Edit::Same));
+}
+
+/// Finds where the shortest path from the start and the shortest path back from the end meet,
+/// searching both ways at once and keeping only the furthest point reached on each diagonal.
+/// Both sequences must be non-empty, and differ in their first and last items.
+fn middle<T: PartialEq>(a: &[T], b: &[T]) -> Option<(usize, usize)> {
+    let (n, m)  = (a.len() as isize, b.len() as isize);
+    let max     = (n + m + 1) / 2;
+    let delta   = n - m;
+    let odd     = delta % 2 != 0;
+
+    // furthest x reached on each diagonal, from the front and from the back
+    let size = 2 * max + 2;
+    let mut forward  = vec![-1isize; size as usize];
+    let mut backward = vec![-1isize; size as usize];
+    forward[max as usize + 1]  = 0;
+    backward[max as usize + 1] = 0;
+
+    // diagonals that ran off the edge are not extended again
+    let (mut f_start, mut f_end, mut b_start, mut b_end) = (0, 0, 0, 0);
+
+    for d in 0..max {
+        let mut k = -d + f_start;
+        while k <= d - f_end {
+            let i = (max + k) as usize;
+            let mut x = if k == -d || (k != d && forward[i - 1] < forward[i + 1]) {
+                forward[i + 1]
+            } else {
+                forward[i - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1; y += 1;
+            }
+            forward[i] = x;
+
+            if x > n {
+                f_end += 2;
+            } else if y > m {
+                f_start += 2;
+            } else if odd {
+                let j = max + delta - k;
+                if j >= 0 && j < size && backward[j as usize] != -1 && x >= n - backward[j as usize] {
+                    return Some((x as usize, y as usize));
+                }
+            }
+            k += 2;
+        }
+
+        let mut k = -d + b_start;
+        while k <= d - b_end {
+            let i = (max + k) as usize;
+            let mut x = if k == -d || (k != d && backward[i - 1] < backward[i + 1]) {
+                backward[i + 1]
+            } else {
+                backward[i - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[(n - x - 1) as usize] == b[(m - y - 1) as usize] {
+                x += 1; y += 1;
+            }
+            backward[i] = x;
+
+            if x > n {
+                b_end += 2;
+            } else if y > m {
+                b_start += 2;
+            } else if !odd {
+                let j = max + delta - k;
+                if j >= 0 && j < size && forward[j as usize] != -1 {
+                    let front = forward[j as usize];
+                    if front >= n - x {
+                        return Some((front as usize, (front - (j - max)) as usize));
+                    }
+                }
+            }
+            k += 2;
+        }
+    }
+    return None;
+}
+
+/// Splits a line into alternating runs of whitespace and everything else,
+/// so that joining the words gives back the line.
+fn words(line: &str) -> Vec<String> {
+    let mut words: Vec<String> = vec![];
+    for c in line.chars() {
+        match words.last_mut() {
+            Some(w) if w.chars().last().is_some_and(|l| l.is_whitespace() == c.is_whitespace()) => w.push(c),
+            _ => words.push(c.to_string()),
+        }
+    }
+    return words;
+}
+
+/// A line of a diff, with the words that changed within it marked.
+#[derive(Debug, Clone)]
+pub struct Line {
+    pub kind:  &'static str, // "same", "added", or "removed"
+    pub words: Vec<(String, bool)>,
+}
+
+impl Line {
+    fn whole(kind: &'static str, line: String) -> Line {
+        Line { kind, words: vec![(line, false)] }
+    }
+}
+
+/// Pairs up a run of removed lines with the added lines that replaced them,
+/// marking the words that changed.
+fn pair(removed: &mut Vec<String>, added: &mut Vec<String>, result: &mut Vec<Line>) {
+    let paired = removed.len().min(added.len());
+    let extra_removed = removed.split_off(paired);
+    let extra_added   = added.split_off(paired);
+
+    let mut old = vec![];
+    let mut new = vec![];
+    for (r, a) in removed.drain(..).zip(added.drain(..)) {
+        let (mut o, mut n) = (vec![], vec![]);
+        for edit in diff(&words(&r), &words(&a)) {
+            match edit {
+                Edit::Same(w)    => { o.push((w.clone(), false)); n.push((w, false)); },
+                Edit::Removed(w) => o.push((w, true)),
+                Edit::Added(w)   => n.push((w, true)),
+            }
+        }
+        old.push(Line { kind: "removed", words: o });
+        new.push(Line { kind: "added",   words: n });
+    }
+    old.extend(extra_removed.into_iter().map(|l| Line::whole("removed", l)));
+    new.extend(extra_added.into_iter().map(|l| Line::whole("added", l)));
+    result.append(&mut old);
+    result.append(&mut new);
+}
+
+/// A line-level diff of two texts.
+/// Runs of removed lines followed by added lines are paired up and diffed word by word.
+pub fn lines(before: &str, after: &str) -> Vec<Line> {
+    let a = before.lines().map(|l| l.to_owned()).collect::<Vec<String>>();
+    let b = after.lines().map(|l| l.to_owned()).collect::<Vec<String>>();
+
+    let mut result  = vec![];
+    let mut removed = vec![];
+    let mut added   = vec![];
+
+    for edit in diff(&a, &b) {
+        match edit {
+            Edit::Removed(l) => removed.push(l),
+            Edit::Added(l)   => added.push(l),
+            Edit::Same(l)    => {
+                pair(&mut removed, &mut added, &mut result);
+                result.push(Line::whole("same", l));
+            },
+        }
+    }
+    pair(&mut removed, &mut added, &mut result);
+    return result;
+}
+
+/// Carries what each line of a text is annotated with over to a newer text.
+/// Lines that are new in the newer text are annotated with `by`.
+pub fn blame<T: Clone>(before: Vec<(String, T)>, after: &str, by: T) -> Vec<(String, T)> {
+    let a = before.iter().map(|(l, _)| l.to_owned()).collect::<Vec<String>>();
+    let b = after.lines().map(|l| l.to_owned()).collect::<Vec<String>>();
+
+    let mut previous = before.into_iter().map(|(_, t)| t);
+    let mut blamed   = vec![];
+    for edit in diff(&a, &b) {
+        match edit {
+            Edit::Removed(_) => { previous.next(); },
+            Edit::Added(l)   => blamed.push((l, by.clone())),
+            Edit::Same(l)    => blamed.push((l, previous.next().unwrap_or_else(|| by.clone()))),
+        }
+    }
+    return blamed;
+}
+
+/// Everything that differs about a page between two versions.
+/// A page missing from one side is compared against an empty page.
+pub struct PageDiff {
+    pub title:    Option<(String, String)>,
+    pub fields:   Vec<(String, Option<String>, Option<String>)>, // key, before, after
+    pub children: Vec<(String, bool)>, // title, whether it was added or removed
+    pub content:  Option<Vec<Line>>, // None if the content is identical
+}
+
+impl PageDiff {
+    /// Compares fields key by key, in key order.
+    pub fn fields(
+        before: &HashMap<String, String>,
+        after:  &HashMap<String, String>,
+    ) -> Vec<(String, Option<String>, Option<String>)> {
+        let mut keys = before.keys().chain(after.keys()).cloned().collect::<Vec<String>>();
+        keys.sort();
+        keys.dedup();
+
+        keys.into_iter()
+            .map(|k| (k.to_owned(), before.get(&k).cloned(), after.get(&k).cloned()))
+            .filter(|(_, b, a)| b != a)
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.fields.is_empty() && self.children.is_empty() && self.content.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Length of the longest common subsequence, the slow way.
+    fn lcs(a: &[u8], b: &[u8]) -> usize {
+        let mut row = vec![0; b.len() + 1];
+        for x in a.iter() {
+            let mut diagonal = 0;
+            for (j, y) in b.iter().enumerate() {
+                let above = row[j + 1];
+                row[j + 1] = if x == y { diagonal + 1 } else { above.max(row[j]) };
+                diagonal = above;
+            }
+        }
+        return row[b.len()];
+    }
+
+    fn check(a: &[u8], b: &[u8]) {
+        let edits = diff(a, b);
+        let before = edits.iter().filter_map(|e| match e { Edit::Same(c) | Edit::Removed(c) => Some(*c), _ => None }).collect::<Vec<u8>>();
+        let after  = edits.iter().filter_map(|e| match e { Edit::Same(c) | Edit::Added(c) => Some(*c), _ => None }).collect::<Vec<u8>>();
+        assert_eq!((before.as_slice(), after.as_slice()), (a, b));
+
+        let same = edits.iter().filter(|e| matches!(e, Edit::Same(_))).count();
+        assert_eq!(same, lcs(a, b), "not the shortest script for {:?} -> {:?}", a, b);
+    }
+
+    #[test]
+    fn diff_gives_the_shortest_script() {
+        check(b"", b"");
+        check(b"abc", b"");
+        check(b"", b"abc");
+        check(b"abcabba", b"cbabac");
+        check(b"xaxbxcx", b"abc");
+
+        // every pair from a small pseudo-random generator, over a small alphabet
+        let mut seed: u32 = 7;
+        let mut next = || { seed = seed.wrapping_mul(1103515245).wrapping_add(12345); (seed >> 16) as usize };
+        for _ in 0..500 {
+            let a = (0..next() % 12).map(|_| b"abc"[next() % 3]).collect::<Vec<u8>>();
+            let b = (0..next() % 12).map(|_| b"abc"[next() % 3]).collect::<Vec<u8>>();
+            check(&a, &b);
+        }
+    }
+
+    #[test]
+    fn diff_handles_long_texts() {
+        let before = (0..20000).map(|i| i.to_string()).collect::<Vec<String>>();
+        let mut after = before.clone();
+        after[5000] = "changed".to_owned();
+        after.insert(15000, "inserted".to_owned());
+
+        let changed = diff(&before, &after).into_iter()
+            .filter(|e| !matches!(e, Edit::Same(_)))
+            .count();
+        assert_eq!(changed, 3);
+    }
+
+    #[test]
+    fn lines_pair_changed_words() {
+        let lines = lines("one two\nthree", "one too\nthree");
+        assert_eq!(lines.iter().map(|l| l.kind).collect::<Vec<&str>>(), vec!["removed", "added", "same"]);
+        assert!(lines[1].words.contains(&("too".to_owned(), true)));
+        assert!(lines[1].words.contains(&("one".to_owned(), false)));
+    }
+}
@@ -0,0 +1,172 @@
+use std::fmt;
+use std::collections::HashMap;
+use time::Date;
+use crate::hrdb::page;
+
+/// A field's value, typed by how it is written.
+/// Fields are stored as text, so values are read back with `Value::parse`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Date(String), // 2020-07-04
+    Bool(bool),   // true or false
+    List(Vec<String>), // [one, two, three]
+    Text(String), // anything else, quoted if it would look like one of the above
+}
+
+/// Whether a key must hold a date, like `date` or `published_date`.
+fn dated(key: &str) -> bool {
+    key == "date" || key.ends_with("_date")
+}
+
+fn looks_like_date(raw: &str) -> bool {
+    raw.len() == 10
+        && raw.char_indices().all(|(i, c)| if i == 4 || i == 7 { c == '-' } else { c.is_ascii_digit() })
+}
+
+fn unquote(raw: &str) -> Option<&str> {
+    if raw.len() >= 2 && raw.starts_with('"') && raw.ends_with('"') {
+        Some(&raw[1..raw.len() - 1])
+    } else {
+        None
+    }
+}
+
+impl Value {
+    pub fn parse(raw: &str) -> Result<Value, String> {
+        let raw = raw.trim();
+        if let Some(text) = unquote(raw) {
+            return Ok(Value::Text(text.to_owned()));
+        }
+
+        match raw {
+            "true"  => return Ok(Value::Bool(true)),
+            "false" => return Ok(Value::Bool(false)),
+            _ => (),
+        }
+
+        if raw.starts_with('[') {
+            if !raw.ends_with(']') {
+                return Err(format!("List '{}' is missing its closing bracket", raw));
+            }
+            let items = raw[1..raw.len() - 1].split(',')
+                .map(|i| i.trim())
+                .filter(|i| !i.is_empty())
+                .map(|i| unquote(i).unwrap_or(i).to_owned())
+                .collect::<Vec<String>>();
+            return Ok(Value::List(items));
+        }
+
+        if looks_like_date(raw) {
+            let number = |range: std::ops::Range<usize>| raw[range].parse::<u16>().unwrap_or(0);
+            Date::try_from_ymd(number(0..4) as i32, number(5..7) as u8, number(8..10) as u8)
+                .ok().ok_or(format!("'{}' is not a real date", raw))?;
+            return Ok(Value::Date(raw.to_owned()));
+        }
+
+        return Ok(Value::Text(raw.to_owned()));
+    }
+
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Value::Date(_) => "date",
+            Value::Bool(_) => "bool",
+            Value::List(_) => "list",
+            Value::Text(_) => "text",
+        }
+    }
+}
+
+/// How the value is written, which always parses back to the same value.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Date(d) => write!(f, "{}", d),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::List(items) => write!(f, "[{}]", items.join(", ")),
+            Value::Text(t) => match Value::parse(t) {
+                Ok(Value::Text(_)) if t.trim() == t && unquote(t).is_none() => write!(f, "{}", t),
+                _ => write!(f, "\"{}\"", t),
+            },
+        }
+    }
+}
+
+/// Reads a front matter block of `key: value` lines into fields.
+/// Blank lines and lines starting with `#` are skipped.
+/// Every value is checked and stored as it would be written back.
+pub fn parse(block: &str) -> Result<HashMap<String, String>, String> {
+    let mut fields = HashMap::new();
+
+    for (number, line) in block.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') { continue; }
+
+        let mut parts = line.splitn(2, ':');
+        let key = parts.next().unwrap_or("").trim().to_lowercase();
+        let raw = parts.next()
+            .ok_or(format!("Line {} should look like 'key: value'", number + 1))?;
+
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(format!("Field name on line {} may only contain letters, numbers, dashes, and underscores", number + 1));
+        }
+        if fields.contains_key(&key) {
+            return Err(format!("Field '{}' is given more than once", key));
+        }
+
+        let value = Value::parse(raw)?;
+        if dated(&key) && value.kind() != "date" {
+            return Err(format!("Field '{}' must be a date, like 2020-07-04", key));
+        }
+        if key == page::SLUG && !page::is_slug(&value.to_string()) {
+            return Err(format!("Field '{}' may only contain lowercase letters, numbers, and dashes, and can't be a name the site already uses", key));
+        }
+        fields.insert(key, value.to_string());
+    }
+
+    return Ok(fields);
+}
+
+/// Writes fields as a front matter block, one per line in key order.
+pub fn block(fields: &HashMap<String, String>) -> String {
+    let mut keys = fields.keys().collect::<Vec<&String>>();
+    keys.sort();
+    keys.into_iter()
+        .map(|k| format!("{}: {}", k, fields[k]))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_typed_by_how_they_are_written() {
+        assert_eq!(Value::parse("2020-07-04"), Ok(Value::Date("2020-07-04".to_owned())));
+        assert_eq!(Value::parse(" true "), Ok(Value::Bool(true)));
+        assert_eq!(Value::parse("[a, \"b\", ]"), Ok(Value::List(vec!["a".to_owned(), "b".to_owned()])));
+        assert_eq!(Value::parse("\"true\""), Ok(Value::Text("true".to_owned())));
+        assert!(Value::parse("2020-02-30").is_err());
+        assert!(Value::parse("[a, b").is_err());
+
+        for raw in ["plain", "\"false\"", "\"2020-07-04\"", "[x, y]"].iter() {
+            let value = Value::parse(raw).unwrap();
+            assert_eq!(Value::parse(&value.to_string()), Ok(value));
+        }
+    }
+
+    #[test]
+    fn blocks_parse_and_write_back() {
+        let fields = parse("# a comment\ntitle: \"Hello\"\n\nDate: 2020-07-04\ntags: [a, b]").unwrap();
+        assert_eq!(block(&fields), "date: 2020-07-04\ntags: [a, b]\ntitle: Hello");
+        assert_eq!(parse(&block(&fields)).unwrap(), fields);
+
+        assert!(parse("no colon here").is_err());
+        assert!(parse("a: 1\na: 2").is_err());
+        assert!(parse("bad key!: 1").is_err());
+        assert!(parse("published_date: soon").is_err());
+        assert!(parse("slug: Not A Slug").is_err());
+        assert!(parse("slug: search").is_err());
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::{HashMap, HashSet};
+use serde::de::DeserializeOwned;
+use serde_json;
+use crate::hrdb::utils::*;
+use crate::hrdb::page::Page;
+use crate::hrdb::commit::Commit;
+use crate::hrdb::search::Index;
+use crate::hrdb::paths::Paths;
+use crate::hrdb::taxonomy::Taxonomy;
+
+/// Something wrong with the store.
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub kind:   &'static str, // "corrupt", "malformed", "dangling", "history", "unreachable", "shorthand", or "incomplete"
+    pub key:    String,
+    pub detail: String,
+}
+
+/// How many objects a check reads at most.
+/// A Worker may make 1000 subrequests while handling a request, and every KV read is one,
+/// so some are left over for listing keys and reading branches.
+pub const READS: usize = 900;
+
+/// What an integrity check found.
+pub struct Report {
+    pub objects:  usize,
+    pub unread:   usize, // listed, but left unchecked to stay within `READS`
+    pub problems: Vec<Problem>,
+}
+
+/// Every object in the store, and which of them have been reached so far.
+/// Objects are checked against their address as they are loaded,
+/// and references are resolved against what was loaded rather than read again.
+/// Past `READS` objects, the rest are only known to exist.
+pub struct Objects {
+    values:       HashMap<String, String>,
+    unread:       HashSet<String>,
+    reached:      HashSet<String>,
+    pub problems: Vec<Problem>,
+}
+
+impl Objects {
+    pub async fn load() -> Result<Objects, String> {
+        let mut objects = Objects {
+            values:   HashMap::new(),
+            unread:   HashSet::new(),
+            reached:  HashSet::new(),
+            problems: vec![],
+        };
+
+        let mut keys = keys().await
+            .map_err(|e| format!("Could not list every object, so nothing was checked: {}", e))?;
+        keys.retain(|k| is_address(k));
+        keys.sort();
+        objects.unread = keys.split_off(keys.len().min(READS)).into_iter().collect();
+        if !objects.unread.is_empty() {
+            objects.problem("incomplete", "objects", format!(
+                "Only {} objects were checked, since a request may only make so many reads; \
+                 the other {} were not, and unreachable objects are not reported",
+                keys.len(),
+                objects.unread.len(),
+            ));
+        }
+
+        for key in keys.into_iter() {
+            let value = read(&key).await?;
+            if hash(&value) != key {
+                objects.problem("corrupt", &key, "Does not hash to its own address".to_owned());
+            }
+            objects.values.insert(key, value);
+        }
+        return Ok(objects);
+    }
+
+    /// How many objects were read and checked.
+    pub fn checked(&self) -> usize {
+        self.values.len()
+    }
+
+    /// How many objects exist, but were not read.
+    pub fn unread(&self) -> usize {
+        self.unread.len()
+    }
+
+    pub fn problem(&mut self, kind: &'static str, key: &str, detail: String) {
+        self.problems.push(Problem { kind, key: key.to_owned(), detail });
+    }
+
+    /// Follows a reference, noting the object as reached, or as dangling if it is missing.
+    /// Objects that were not read can not be followed any further.
+    fn get(&mut self, address: &str, from: &str) -> Option<String> {
+        match self.values.get(address) {
+            Some(v) => {
+                self.reached.insert(address.to_owned());
+                Some(v.to_owned())
+            },
+            None if self.unread.contains(address) => {
+                self.reached.insert(address.to_owned());
+                None
+            },
+            // only report each missing object once
+            None => {
+                if self.reached.insert(address.to_owned()) {
+                    self.problem("dangling", address, format!("Referenced by {}, but missing", from));
+                }
+                None
+            },
+        }
+    }
+
+    fn parse<T: DeserializeOwned>(&mut self, address: &str, from: &str, what: &str) -> Option<T> {
+        let value = self.get(address, from)?;
+        match serde_json::from_str(&value) {
+            Ok(t)  => Some(t),
+            Err(_) => {
+                self.problem("malformed", address, format!("Referenced by {} as a {}, but is not one", from, what));
+                None
+            },
+        }
+    }
+
+    /// Walks the tree under a version, resolving every page and its content.
+    /// Versions share unchanged subtrees, so a reached page is not walked again.
+    pub fn tree(&mut self, root: &str, from: &str) {
+        let mut queue = vec![(root.to_owned(), from.to_owned())];
+        while let Some((address, from)) = queue.pop() {
+            if self.reached.contains(&address) { continue; }
+            let page: Page = match self.parse(&address, &from, "Page") {
+                Some(p) => p,
+                None    => continue,
+            };
+
+            let here = format!("Page '{}'", page.title);
+            self.get(&page.content, &here);
+            for child in page.children.values() {
+                queue.push((child.to_owned(), here.to_owned()));
+            }
+        }
+    }
+
+    /// Checks that a commit belongs to its version, and follows on from the one before it.
+    /// The first commit on a branch may point at a parent garbage collection has dropped.
+    pub fn commit(&mut self, address: &str, root: &str, previous: Option<&str>, from: &str) {
+        let commit: Commit = match self.parse(address, from, "Commit") {
+            Some(c) => c,
+            None    => return,
+        };
+
+        if commit.root != root {
+            self.problem("history", address, format!("Commit of {} records a different root", from));
+        }
+        if let Some(previous) = previous {
+            if commit.parent.as_deref() != Some(previous) {
+                self.problem("history", address, format!("Commit of {} does not follow the version before it", from));
+            }
+        }
+    }
+
+    /// Checks that a version's `Paths` index starts at the version's root.
+    /// Every other address in it is checked by walking the tree.
+    pub fn paths(&mut self, address: &str, root: &str, from: &str) {
+        let paths: Paths = match self.parse(address, from, "Paths index") {
+            Some(p) => p,
+            None    => return,
+        };
+        if paths.root().as_deref() != Some(root) {
+            self.problem("malformed", address, format!("Paths index of {} does not start at its root", from));
+        }
+    }
+
+    /// Whether a loaded `Paths` index has a page, or `None` if it was not loaded.
+    pub fn indexes(&self, address: &str, id: &str) -> Option<bool> {
+        let paths: Paths = serde_json::from_str(self.values.get(address)?).ok()?;
+        Some(paths.get(id).is_some())
+    }
+
+    pub fn index(&mut self, address: &str, from: &str) {
+        let _: Option<Index> = self.parse(address, from, "search Index");
+    }
+
+    pub fn taxonomy(&mut self, address: &str) {
+        let from = "the taxonomy";
+        if let Some(taxonomy) = self.parse::<Taxonomy>(address, from, "Taxonomy") {
+            self.tree(&taxonomy.version, from);
+        }
+    }
+
+    /// Reports every object that nothing reached.
+    /// Without every object read, some of what they refer to is never reached,
+    /// so nothing is reported.
+    pub fn unreachable(&mut self) {
+        if !self.unread.is_empty() {
+            return;
+        }
+        let mut unreached = self.values.keys()
+            .filter(|k| !self.reached.contains(*k))
+            .cloned()
+            .collect::<Vec<String>>();
+        unreached.sort();
+
+        for address in unreached.iter() {
+            self.problem("unreachable", address, "Not referenced by any version".to_owned());
+        }
+    }
+}
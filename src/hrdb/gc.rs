@@ -0,0 +1,102 @@
+use serde::{Serialize, Deserialize};
+use serde_json;
+use std::collections::{HashMap, HashSet};
+use crate::hrdb::utils::*;
+use crate::hrdb::page::Page;
+use crate::hrdb::branch::Branch;
+use crate::hrdb::commit::Commit;
+use crate::hrdb::fsck::READS;
+
+/// Milliseconds in a day.
+pub const DAY: u64 = 24 * 60 * 60 * 1000;
+
+/// How long an object must have been unreachable before it is swept, in milliseconds.
+/// Changes write their objects before the branch list points at them,
+/// so anything found unreachable more recently may belong to one still being made.
+pub const GRACE: u64 = 60 * 60 * 1000;
+
+/// `Garbage` maps each object found unreachable to when it was first found so.
+/// Stored under `garbage`, so a later collection can sweep what stayed unreachable.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Garbage(HashMap<String, u64>);
+
+impl Garbage {
+    pub async fn read() -> Result<Garbage, String> {
+        match read("garbage").await {
+            Ok(s)  => serde_json::from_str(&s)
+                .ok().ok_or("Could not deserialize Garbage".to_owned()),
+            Err(_) => Ok(Garbage::default()),
+        }
+    }
+
+    pub async fn write(&self) -> Result<(), String> {
+        let serialized = serde_json::to_string_pretty(self)
+            .ok().ok_or("Could not serialize Garbage")?;
+        mutate("garbage", &serialized).await
+    }
+
+    /// Remembers exactly the unreachable objects given,
+    /// keeping when each was first found for those already known.
+    pub fn update(&mut self, unreachable: &[String], now: u64) {
+        let before = std::mem::take(&mut self.0);
+        self.0 = unreachable.iter()
+            .map(|a| (a.to_owned(), before.get(a).cloned().unwrap_or(now)))
+            .collect();
+    }
+
+    /// Whether an object was already unreachable before the cutoff.
+    pub fn since(&self, address: &str, cutoff: u64) -> bool {
+        self.0.get(address).is_some_and(|found| *found <= cutoff)
+    }
+
+    /// Each object, with when it was first found unreachable.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &u64)> {
+        self.0.iter()
+    }
+}
+
+/// What a collection removed, or would remove on a dry run.
+pub struct Report {
+    pub dry_run:    bool,
+    pub reachable:  usize,
+    pub pruned:     Vec<(String, usize)>, // branch, versions dropped by the retention policy
+    pub swept:      Vec<String>, // unreachable addresses
+    pub pending:    usize, // unreachable, but not for long enough to be swept
+    pub deferred:   usize, // unreachable for long enough, but left for the next collection to stay within `READS`
+    pub incomplete: bool, // marking stopped at `READS`, so nothing was swept
+}
+
+/// Marks a page, its content, and every page nested under it, counting each page read.
+/// Versions share unchanged subtrees, so a page that is already marked is not walked again.
+/// Stops once `READS` pages have been read, leaving the rest of the tree unmarked.
+pub async fn mark(root: &str, marked: &mut HashSet<String>, reads: &mut usize) -> Result<(), String> {
+    let mut queue = vec![root.to_owned()];
+    while let Some(address) = queue.pop() {
+        if *reads >= READS { return Ok(()); }
+        if !marked.insert(address.to_owned()) { continue; }
+        *reads += 1;
+        let page = Page::from(&address).await?;
+        marked.insert(page.content.to_owned());
+        queue.extend(page.children.values().cloned());
+    }
+    return Ok(());
+}
+
+/// How many of the oldest versions still on a branch were committed before the cutoff.
+/// The head and every version in `keep` are never expired,
+/// and neither is anything after them, so what remains of the history is contiguous.
+/// Versions without a commit are as old as can be. Each commit read is counted.
+pub async fn expired(branch: &Branch, cutoff: u64, keep: &HashSet<String>, reads: &mut usize) -> Result<usize, String> {
+    let older = branch.versions.len().saturating_sub(1);
+    let mut count = 0;
+    for (version, commit) in branch.versions.iter().zip(branch.commits.iter()).take(older).skip(branch.pruned()) {
+        if keep.contains(version) { break; }
+        let timestamp = match commit {
+            Some(c) => { *reads += 1; Commit::from(c).await?.timestamp },
+            None    => 0,
+        };
+        if timestamp >= cutoff { break; }
+        count += 1;
+    }
+    return Ok(count);
+}
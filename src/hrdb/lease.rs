@@ -0,0 +1,46 @@
+use crate::hrdb::utils::*;
+
+/// How long a lease lasts if its holder never releases it, in milliseconds.
+const DURATION: u64 = 30 * 1000;
+
+/// A short-lived claim on a branch, so only one change is applied to its head at a time.
+/// Stored next to the branch list as a random token and an expiry time.
+/// KV is only eventually consistent, so this narrows the window for lost updates
+/// rather than closing it; the head is still checked under the lease.
+pub struct Lease {
+    key:   String,
+    token: String,
+}
+
+impl Lease {
+    pub async fn acquire(branch: &str) -> Result<Lease, String> {
+        let key = format!("{}.lease", branch);
+        let busy = format!("Branch {} is being changed by someone else; try again in a moment", branch);
+
+        if let Ok(held) = read(&key).await {
+            let expires = held.split_whitespace().nth(1)
+                .and_then(|e| e.parse::<u64>().ok())
+                .unwrap_or(0);
+            if expires > now()? {
+                return Err(busy);
+            }
+        }
+
+        let token = stamp()?;
+        mutate(&key, &format!("{} {}", token, now()? + DURATION)).await?;
+
+        // whoever wrote last holds the lease
+        if !read(&key).await?.starts_with(&token) {
+            return Err(busy);
+        }
+        return Ok(Lease { key, token });
+    }
+
+    /// Gives up the lease, unless it has already expired and been taken by someone else.
+    pub async fn release(self) -> Result<(), String> {
+        match read(&self.key).await {
+            Ok(held) if held.starts_with(&self.token) => remove(&self.key).await,
+            _ => Ok(()),
+        }
+    }
+}
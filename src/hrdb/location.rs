@@ -32,6 +32,8 @@ impl Location {
         Ok(((self.0).1).clone().ok_or("Location does not specify a version")?.0)
     }
 
+    /// The number of the first version with this location's root.
+    /// Resets bring back old roots, so prefer a number resolved from a path where there is one.
     pub async fn ver_no(&self) -> Result<usize, String> {
         let version = self.version()?;
         controller::versions(self.clone()).await?
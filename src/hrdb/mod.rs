@@ -2,6 +2,20 @@ pub mod controller;
 pub mod location;
 pub mod shorthand;
 pub mod utils;
+pub mod store;
+pub mod change;
+pub mod search;
+pub mod bloom;
+pub mod commit;
+pub mod diff;
+pub mod gc;
+pub mod fsck;
+pub mod bundle;
+pub mod lease;
+pub mod paths;
+pub mod fields;
+pub mod taxonomy;
+pub mod release;
 
 mod branch;
 mod page;
@@ -1,16 +1,150 @@
-use serde::{Serialize, Deserialize};
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
 use serde_json;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use sha2::Digest;
 use crate::hrdb::utils::*;
 
-#[derive(Serialize, Deserialize)]
+/// The field a page's shorthand can be set with, overriding the one derived from its title.
+pub const SLUG: &str = "slug";
+
+/// A title as it is written in a shorthand.
+/// Letters and digits in any script are kept, lowercased, and spaces become dashes;
+/// everything else is dropped.
+pub fn slugify(title: &str) -> String {
+    title
+        .chars()
+        .filter(|x| x.is_alphanumeric() || x == &' ')
+        .collect::<String>()
+        .split(" ")
+        .collect::<Vec<&str>>()
+        .join("-")
+        .to_lowercase()
+}
+
+/// The first parts of paths the site answers itself, which a shorthand would never be looked up at.
+pub const ROUTES: &[&str] = &[
+    "init", "static", "perma", "diff", "history", "blame", "branches", "branch", "versions",
+    "auth", "edit", "create", "delete", "order", "restore", "reset", "search", "tags",
+    "relocate", "duplicate", "fork", "release", "releases", "merge", "pick", "gc", "fsck",
+    "bundle", "dump",
+];
+
+/// Whether a slug field can be used as a shorthand as written.
+pub fn is_slug(slug: &str) -> bool {
+    !slug.is_empty()
+        && !ROUTES.contains(&slug)
+        && slug.chars().any(|c| c != '-')
+        && slug.chars().all(|c| c == '-' || (c.is_alphanumeric() && !c.is_uppercase()))
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Page {
     id:           String,
+    #[serde(serialize_with = "sorted")]
     pub fields:   HashMap<String, String>,
     pub title:    String,
     pub content:  String, // Content
-    pub children: HashMap<String, String>, // id -> page checksum
+    pub children: Children,
+}
+
+/// Fields are written in key order, so the same page always has the same address.
+fn sorted<S: Serializer>(fields: &HashMap<String, String>, serializer: S) -> Result<S::Ok, S::Error> {
+    fields.iter().collect::<BTreeMap<&String, &String>>().serialize(serializer)
+}
+
+/// The pages nested under a page, in order, as id and page checksum.
+/// Pages written before children were ordered stored them as a map,
+/// which is read back ordered by id.
+#[derive(Clone, Default, PartialEq, Serialize)]
+pub struct Children(Vec<(String, String)>);
+
+impl<'de> Deserialize<'de> for Children {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Children, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Stored {
+            Ordered(Vec<(String, String)>),
+            Legacy(BTreeMap<String, String>),
+        }
+
+        Ok(match Stored::deserialize(deserializer)? {
+            Stored::Ordered(children) => Children(children),
+            Stored::Legacy(children)  => Children(children.into_iter().collect()),
+        })
+    }
+}
+
+impl Children {
+    pub fn get(&self, id: &str) -> Option<&String> {
+        self.0.iter().find(|(i, _)| i == id).map(|(_, address)| address)
+    }
+
+    pub fn contains_key(&self, id: &str) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// Replaces the address of a child in place, or adds it at the end.
+    pub fn insert(&mut self, id: String, address: String) {
+        match self.0.iter_mut().find(|(i, _)| *i == id) {
+            Some(child) => child.1 = address,
+            None        => self.0.push((id, address)),
+        }
+    }
+
+    pub fn remove(&mut self, id: &str) -> Option<String> {
+        let position = self.0.iter().position(|(i, _)| i == id)?;
+        Some(self.0.remove(position).1)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.0.iter().map(|(id, address)| (id, address))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &String> {
+        self.0.iter().map(|(_, address)| address)
+    }
+
+    pub fn ids(&self) -> Vec<String> {
+        self.0.iter().map(|(id, _)| id.to_owned()).collect()
+    }
+
+    /// Moves a child one place earlier or later,
+    /// returning false if it is already first or last.
+    pub fn shift(&mut self, id: &str, up: bool) -> bool {
+        let position = match self.0.iter().position(|(i, _)| i == id) {
+            Some(p) => p,
+            None    => return false,
+        };
+        let other = match (up, position) {
+            (true, 0) => return false,
+            (true, p) => p - 1,
+            (false, p) if p + 1 >= self.0.len() => return false,
+            (false, p) => p + 1,
+        };
+        self.0.swap(position, other);
+        return true;
+    }
+
+    /// Orders the children by a key taken from each id; the sort is stable.
+    pub fn sort_by_key<K: Ord>(&mut self, key: impl Fn(&str) -> K) {
+        self.0.sort_by_key(|(id, _)| key(id));
+    }
+
+    /// Whether the children both have in common are in the same order.
+    pub fn same_order(&self, other: &Children) -> bool {
+        let ours   = self.0.iter().filter(|(id, _)| other.contains_key(id)).map(|(id, _)| id);
+        let theirs = other.0.iter().filter(|(id, _)| self.contains_key(id)).map(|(id, _)| id);
+        ours.eq(theirs)
+    }
+}
+
+impl IntoIterator for Children {
+    type Item = (String, String);
+    type IntoIter = std::vec::IntoIter<(String, String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
 }
 
 impl Page {
@@ -25,7 +159,7 @@ impl Page {
             fields,
             title,
             content,
-            children: HashMap::new(),
+            children: Children::default(),
         }
     }
 
@@ -33,21 +167,30 @@ impl Page {
         self.id == other.id
     }
 
+    /// Whether two pages have the same title, content and fields,
+    /// regardless of their ids or children.
+    pub fn same_body(&self, other: &Page) -> bool {
+        self.title   == other.title
+        && self.content == other.content
+        && self.fields  == other.fields
+    }
+
     pub fn to_string(&self) -> Result<String, String> {
         let serialized = serde_json::to_string_pretty(self)
             .ok().ok_or("Could not serialize Page")?;
         return Ok(serialized);
     }
 
+    /// The name the page goes by in its shorthand: its slug field if it has one,
+    /// otherwise its title as a slug, or the start of its id if the title has nothing usable.
     pub fn short(&self) -> String {
-        self.title
-            .chars()
-            .filter(|x| x.is_ascii_alphanumeric() || x == &' ')
-            .collect::<String>()
-            .split(" ")
-            .collect::<Vec<&str>>()
-            .join("-")
-            .to_lowercase()
+        if let Some(slug) = self.fields.get(SLUG) {
+            return slug.to_owned();
+        }
+        match slugify(&self.title) {
+            s if s.chars().any(|c| c != '-') => s,
+            _ => self.id.chars().take(8).collect(),
+        }
     }
 
     pub fn id(&self) -> String { self.id.to_owned() }
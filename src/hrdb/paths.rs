@@ -0,0 +1,85 @@
+use serde::{Serialize, Deserialize};
+use serde_json;
+use std::collections::{BTreeMap, HashMap};
+use crate::hrdb::utils::*;
+use crate::hrdb::page::Page;
+
+/// Every page on a version, by id, with the id of its parent and its address.
+/// Stored content-addressed, and recorded as the third token of a branch list line,
+/// so a page can be found on a version with a single read instead of a walk.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Paths(BTreeMap<String, (Option<String>, String)>); // id -> parent id, address
+
+impl Paths {
+    pub async fn from(hash: &str) -> Result<Paths, String> {
+        serde_json::from_str(&read(hash).await?)
+            .ok().ok_or("Could not deserialize Paths".to_owned())
+    }
+
+    pub fn to_string(&self) -> Result<String, String> {
+        let serialized = serde_json::to_string(self)
+            .ok().ok_or("Could not serialize Paths")?;
+        return Ok(serialized);
+    }
+
+    /// The address of the index on a branch list line, if the version has one.
+    pub fn split(line: &str) -> Option<String> {
+        line.split_whitespace().nth(2).map(|p| p.to_owned())
+    }
+
+    /// Indexes the tree under a root.
+    /// Subtrees with the same address as in the previous version are copied
+    /// from its index rather than read again, so only changed pages are read.
+    pub async fn build(root: &str, previous: Option<&Paths>) -> Result<Paths, String> {
+        let empty = Paths::default();
+        let previous = previous.unwrap_or(&empty);
+        let known = previous.0.iter()
+            .map(|(id, (_, address))| (address.to_owned(), id.to_owned()))
+            .collect::<HashMap<String, String>>();
+        let mut nested: HashMap<&String, Vec<&String>> = HashMap::new();
+        for (id, (parent, _)) in previous.0.iter() {
+            if let Some(p) = parent { nested.entry(p).or_default().push(id); }
+        }
+
+        let mut paths = Paths::default();
+        let mut queue = vec![(None, root.to_owned())];
+        while let Some((parent, address)) = queue.pop() {
+            if let Some(id) = known.get(&address) {
+                paths.0.insert(id.to_owned(), (parent, address));
+                let mut copying = nested.get(id).cloned().unwrap_or_default();
+                while let Some(d) = copying.pop() {
+                    paths.0.insert(d.to_owned(), previous.0[d].clone());
+                    copying.extend(nested.get(d).cloned().unwrap_or_default());
+                }
+                continue;
+            }
+
+            let page = Page::from(&address).await?;
+            for child in page.children.values() {
+                queue.push((Some(page.id()), child.to_owned()));
+            }
+            paths.0.insert(page.id(), (parent, address));
+        }
+        return Ok(paths);
+    }
+
+    /// The address of the root page, the only one without a parent.
+    pub fn root(&self) -> Option<String> {
+        self.0.values()
+            .find(|(parent, _)| parent.is_none())
+            .map(|(_, address)| address.to_owned())
+    }
+
+    /// The addresses from the root down to a page.
+    pub fn get(&self, id: &str) -> Option<Vec<String>> {
+        let mut path = vec![];
+        let mut next = Some(id.to_owned());
+        while let Some(id) = next {
+            let (parent, address) = self.0.get(&id)?;
+            path.push(address.to_owned());
+            next = parent.to_owned();
+        }
+        path.reverse();
+        return Some(path);
+    }
+}
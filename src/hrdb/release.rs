@@ -0,0 +1,75 @@
+use serde::{Serialize, Deserialize};
+use serde_json;
+use std::collections::BTreeMap;
+use crate::hrdb::utils::*;
+
+/// A version of a branch given a name, like `2020-launch`.
+/// Releases can not be moved or removed once made.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Release {
+    pub branch:    String,
+    pub version:   String, // root
+    pub author:    String,
+    pub timestamp: u64, // milliseconds since the epoch
+}
+
+/// `Releases` maps each release name to the version it names.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Releases(BTreeMap<String, Release>);
+
+impl Releases {
+    pub async fn read() -> Result<Releases, String> {
+        match read("releases").await {
+            Ok(s)  => serde_json::from_str(&s)
+                .ok().ok_or("Could not deserialize Releases".to_owned()),
+            Err(_) => Ok(Releases::default()),
+        }
+    }
+
+    pub async fn write(&self) -> Result<(), String> {
+        let serialized = serde_json::to_string_pretty(self)
+            .ok().ok_or("Could not serialize Releases")?;
+        mutate("releases", &serialized).await
+    }
+
+    /// Checks that a name can be used for a new release.
+    /// Release names stand in for version numbers in routes,
+    /// so anything that could be read as one is rejected.
+    pub fn validate(name: &str) -> Result<(), String> {
+        if name.is_empty() {
+            return Err("Release names can not be empty".to_owned());
+        }
+        if !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_') {
+            return Err("Release names may only contain lowercase letters, numbers, dashes, and underscores".to_owned());
+        }
+        if name == "head" || name.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("'{}' could be mistaken for a version number", name));
+        }
+        return Ok(());
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Release> {
+        self.0.get(name)
+    }
+
+    /// Adds a release, unless one with the same name already exists.
+    pub fn insert(&mut self, name: String, release: Release) -> Result<(), String> {
+        if self.0.contains_key(&name) {
+            return Err(format!("A Release named {} already exists, and can not be changed", name));
+        }
+        self.0.insert(name, release);
+        return Ok(());
+    }
+
+    /// Points every release on a branch at its new name.
+    pub fn rename(&mut self, from: &str, into: &str) {
+        for release in self.0.values_mut().filter(|r| r.branch == from) {
+            release.branch = into.to_owned();
+        }
+    }
+
+    /// Each release, by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Release)> {
+        self.0.iter()
+    }
+}
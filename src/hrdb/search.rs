@@ -0,0 +1,178 @@
+use serde::{Serialize, Deserialize};
+use serde_json;
+use std::collections::{BTreeMap, HashMap};
+use crate::hrdb::utils::*;
+
+/// Title words count this many times more than words in the content.
+const TITLE_WEIGHT: usize = 3;
+
+/// How many characters of content are shown around a match.
+const SNIPPET_LENGTH: usize = 160;
+
+/// Lowercased runs of alphanumeric characters.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .collect::<Vec<String>>()
+}
+
+/// A short excerpt of the text around the first query term it contains.
+pub fn snippet(text: &str, terms: &[String]) -> String {
+    let lower = text.to_lowercase();
+    let found = terms.iter()
+        .filter_map(|t| lower.find(t.as_str()))
+        .min()
+        .unwrap_or(0);
+
+    // lowercasing can shift byte offsets, so work in characters
+    let at    = lower[..found].chars().count();
+    let start = at.saturating_sub(SNIPPET_LENGTH / 4);
+    let chars = text.chars().collect::<Vec<char>>();
+    let end   = (start + SNIPPET_LENGTH).min(chars.len());
+
+    let mut excerpt = chars[start..end].iter().collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+    if start > 0        { excerpt = "…".to_owned() + &excerpt; }
+    if end < chars.len() { excerpt += "…"; }
+    return excerpt;
+}
+
+/// What the index remembers about each page.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Doc {
+    pub title:   String,
+    pub content: String, // Content
+    length:      usize,
+}
+
+/// A page matching a query.
+pub struct Hit {
+    pub id:    String,
+    pub doc:   Doc,
+    pub score: f64,
+}
+
+/// An inverted index of the words in every page on a version.
+/// Stored content-addressed, like a `Page`.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Index {
+    docs:  BTreeMap<String, Doc>,                        // id -> doc
+    terms: BTreeMap<String, BTreeMap<String, usize>>,    // term -> id -> frequency
+}
+
+/// `Indexes` maps each branch to the version its index is up to date with,
+/// and the address of that index.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Indexes(HashMap<String, (String, String)>);
+
+impl Indexes {
+    pub async fn read() -> Result<Indexes, String> {
+        match read("search").await {
+            Ok(s)  => serde_json::from_str(&s)
+                .ok().ok_or("Could not deserialize search Indexes".to_owned()),
+            Err(_) => Ok(Indexes::default()),
+        }
+    }
+
+    pub async fn write(&self) -> Result<(), String> {
+        let serialized = serde_json::to_string_pretty(self)
+            .ok().ok_or("Could not serialize search Indexes")?;
+        mutate("search", &serialized).await
+    }
+
+    pub fn get(&self, branch: &str) -> Option<(String, String)> {
+        self.0.get(branch).cloned()
+    }
+
+    pub fn set(&mut self, branch: String, version: String, index: String) {
+        self.0.insert(branch, (version, index));
+    }
+
+    /// Forgets the indexes of every branch and version that fails the check,
+    /// so they are rebuilt from scratch the next time they are searched.
+    pub fn retain(&mut self, keep: impl Fn(&str, &str) -> bool) {
+        self.0.retain(|branch, (version, _)| keep(branch, version));
+    }
+
+    /// Each branch, with the version and address of its index.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &(String, String))> {
+        self.0.iter()
+    }
+
+    pub fn addresses(&self) -> Vec<String> {
+        self.0.values().map(|(_, index)| index.to_owned()).collect()
+    }
+}
+
+impl Index {
+    pub async fn from(hash: &str) -> Result<Index, String> {
+        serde_json::from_str(&read(hash).await?)
+            .ok().ok_or("Could not deserialize search Index".to_owned())
+    }
+
+    pub fn to_string(&self) -> Result<String, String> {
+        let serialized = serde_json::to_string(self)
+            .ok().ok_or("Could not serialize search Index")?;
+        return Ok(serialized);
+    }
+
+    pub fn insert(&mut self, id: String, title: String, content: String, text: &str) {
+        self.remove(&id);
+
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for term in tokenize(&title) {
+            *counts.entry(term).or_default() += TITLE_WEIGHT;
+        }
+        for term in tokenize(text) {
+            *counts.entry(term).or_default() += 1;
+        }
+
+        let length = counts.values().sum();
+        for (term, count) in counts.into_iter() {
+            self.terms.entry(term).or_default().insert(id.to_owned(), count);
+        }
+        self.docs.insert(id, Doc { title, content, length });
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        if self.docs.remove(id).is_none() { return; }
+        self.terms.retain(|_, postings| {
+            postings.remove(id);
+            !postings.is_empty()
+        });
+    }
+
+    /// Scores every page containing any of the terms, best first.
+    pub fn query(&self, terms: &[String]) -> Vec<Hit> {
+        // okapi bm25, more or less
+        let (k, b) = (1.2, 0.75);
+        let total  = self.docs.len() as f64;
+        let mean   = self.docs.values().map(|d| d.length).sum::<usize>() as f64 / total.max(1.0);
+
+        let mut scores: HashMap<&String, f64> = HashMap::new();
+        for term in terms.iter() {
+            let postings = match self.terms.get(term) {
+                Some(p) => p,
+                None    => continue,
+            };
+            let n   = postings.len() as f64;
+            let idf = ((total - n + 0.5) / (n + 0.5) + 1.0).ln();
+
+            for (id, count) in postings.iter() {
+                let length = self.docs[id].length as f64;
+                let tf     = *count as f64;
+                *scores.entry(id).or_default() +=
+                    idf * (tf * (k + 1.0)) / (tf + k * (1.0 - b + b * length / mean.max(1.0)));
+            }
+        }
+
+        let mut hits = scores.into_iter()
+            .map(|(id, score)| Hit { id: id.to_owned(), doc: self.docs[id].clone(), score })
+            .collect::<Vec<Hit>>();
+        hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        return hits;
+    }
+}
@@ -1,7 +1,7 @@
 use serde::{Serialize, Deserialize};
 use serde_json;
-use std::collections::HashMap;
-use crate::hrdb::utils::*;
+use std::collections::{BTreeMap, HashMap};
+use crate::hrdb::{page::ROUTES, utils::*};
 
 /// `Shorthand` maps name to a version, id, and bloom filter which can be used to create a `Location`.
 #[derive(Serialize, Deserialize)]
@@ -31,10 +31,132 @@ impl Shorthand {
         mutate("shorthand", &self.to_string()?).await
     }
 
-    pub async fn update(name: String, ver_no: usize, id: String) -> Result<(), String> {
-        let mut table = Shorthand::read().await?.unwrap();
-        table.insert(name, (ver_no, id, "".to_owned()));
+    /// The most recently assigned shorthand pointing to a page id.
+    pub fn find(&self, id: &str) -> Option<String> {
+        self.0.iter()
+            .filter(|(_, (_, i, _))| i == id)
+            .max_by_key(|(_, (ver_no, _, _))| *ver_no)
+            .map(|(name, _)| name.to_owned())
+    }
+
+    /// Points a page's shorthand at the version it last changed in, returning the name it was given.
+    /// A name that belongs, or used to belong, to another page gets a numeric suffix instead,
+    /// and any other name the page had becomes an alias of it.
+    pub async fn update(name: String, ver_no: usize, id: String, bloom: String) -> Result<String, String> {
+        let mut table   = Shorthand::read().await?.unwrap();
+        let mut aliases = Aliases::read().await?;
+        let name = claim(&table, &aliases, &name, &id);
+
+        let retired = table.iter()
+            .filter(|(n, (_, i, _))| i == &id && n != &&name)
+            .map(|(n, _)| n.to_owned())
+            .collect::<Vec<String>>();
+        let renamed = !retired.is_empty() || aliases.0.contains_key(&name);
+        for n in retired.into_iter() {
+            table.remove(&n);
+            aliases.0.insert(n, id.to_owned());
+        }
+        aliases.0.remove(&name);
+
+        table.insert(name.to_owned(), (ver_no, id, bloom));
         Shorthand::wrap(table).write().await?;
+        if renamed {
+            aliases.write().await?;
+        }
+        return Ok(name);
+    }
+
+    /// Keeps only the newest name of each page, making the rest aliases of it.
+    /// Tables written before aliases existed have every name a page ever had.
+    pub async fn settle() -> Result<(), String> {
+        let mut table   = Shorthand::read().await?.unwrap();
+        let mut aliases = Aliases::read().await?;
+
+        let mut newest: HashMap<String, (usize, String)> = HashMap::new(); // id -> ver_no, name
+        for (name, (ver_no, id, _)) in table.iter() {
+            match newest.get(id) {
+                Some((v, n)) if (v, n) >= (ver_no, name) => (),
+                _ => { newest.insert(id.to_owned(), (*ver_no, name.to_owned())); },
+            }
+        }
+
+        let retired = table.iter()
+            .filter(|(name, (_, id, _))| &newest[id].1 != *name)
+            .map(|(name, (_, id, _))| (name.to_owned(), id.to_owned()))
+            .collect::<Vec<(String, String)>>();
+        if retired.is_empty() {
+            return Ok(());
+        }
+        for (name, id) in retired.into_iter() {
+            table.remove(&name);
+            aliases.0.insert(name, id);
+        }
+        Shorthand::wrap(table).write().await?;
+        aliases.write().await?;
         return Ok(());
     }
 }
+
+/// The name a page should be given, which no other page has or used to have,
+/// and which the site doesn't answer itself.
+/// A page that already had to be given a suffix keeps it.
+fn claim(table: &HashMap<String, (usize, String, String)>, aliases: &Aliases, name: &str, id: &str) -> String {
+    let taken = |n: &str| {
+        ROUTES.contains(&n)
+            || table.get(n).is_some_and(|(_, i, _)| i != id)
+            || aliases.0.get(n).is_some_and(|i| i != id)
+    };
+    if !taken(name) {
+        return name.to_owned();
+    }
+
+    let suffixed = |n: &str| n.strip_prefix(name)
+        .and_then(|rest| rest.strip_prefix('-'))
+        .is_some_and(|rest| !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit()));
+    let kept = table.iter()
+        .find(|(n, (_, i, _))| i == id && suffixed(n) && !taken(n))
+        .map(|(n, _)| n.to_owned());
+    if let Some(n) = kept {
+        return n;
+    }
+
+    return (2..)
+        .map(|n| format!("{}-{}", name, n))
+        .find(|n| !taken(n))
+        .unwrap_or_default();
+}
+
+/// `Aliases` maps the names pages used to have to their ids,
+/// so links to an old name can be sent on to the page's current shorthand.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Aliases(BTreeMap<String, String>);
+
+impl Aliases {
+    pub async fn read() -> Result<Aliases, String> {
+        match read("aliases").await {
+            Ok(s)  => serde_json::from_str(&s)
+                .ok().ok_or("Could not deserialize Aliases".to_owned()),
+            Err(_) => Ok(Aliases::default()),
+        }
+    }
+
+    pub async fn write(&self) -> Result<(), String> {
+        let serialized = serde_json::to_string_pretty(self)
+            .ok().ok_or("Could not serialize Aliases")?;
+        mutate("aliases", &serialized).await
+    }
+
+    /// The id of the page that used to have a name.
+    pub fn get(&self, name: &str) -> Option<&String> {
+        self.0.get(name)
+    }
+
+    pub fn insert(&mut self, name: String, id: String) {
+        self.0.insert(name, id);
+    }
+
+    /// Each old name, with the id of its page.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.0.iter()
+    }
+}
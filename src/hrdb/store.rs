@@ -0,0 +1,205 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+use futures::future::{self, LocalBoxFuture};
+use serde::{Serialize, Deserialize};
+use wasm_bindgen::JsValue;
+use crate::kv;
+
+/// A `Store` is where HRDB keeps its objects.
+/// Content-addressed objects and the mutable lists (branches, `hrdb`, `shorthand`)
+/// share a single flat namespace of string keys to string values.
+/// Reading a key that does not exist is an error.
+pub trait Store {
+    fn get<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, Result<String, String>>;
+    fn put<'a>(&'a self, key: &'a str, value: &'a str) -> LocalBoxFuture<'a, Result<(), String>>;
+    fn delete<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, Result<(), String>>;
+    fn keys(&self) -> LocalBoxFuture<'_, Result<Vec<String>, String>>;
+}
+
+thread_local! {
+    static CURRENT: RefCell<Rc<dyn Store>> = RefCell::new(Rc::new(KV));
+}
+
+/// Returns the `Store` HRDB is currently using.
+/// Defaults to the `AddressedNS` KV binding.
+pub fn current() -> Rc<dyn Store> {
+    CURRENT.with(|c| c.borrow().clone())
+}
+
+/// Swaps out the `Store` HRDB reads from and writes to on this thread.
+pub fn using(store: Rc<dyn Store>) {
+    CURRENT.with(|c| *c.borrow_mut() = store);
+}
+
+// cloudflare kv
+
+#[derive(Debug, Serialize, Deserialize)]
+struct KeysRust {
+    list_complete: bool,
+    keys: Vec<KeyRust>,
+    #[serde(default)]
+    cursor: Option<String>,
+}
+
+/// Where to carry on listing from; KV lists at most 1000 keys at a time.
+#[derive(Debug, Serialize)]
+struct ListOptions {
+    cursor: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct KeyRust {
+    name: String,
+}
+
+/// The `AddressedNS` namespace of a Cloudflare Worker.
+pub struct KV;
+
+impl Store for KV {
+    fn get<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, Result<String, String>> {
+        Box::pin(async move {
+            kv::value(kv::AddressedNS::get(key, "text"))
+                .await.ok_or("Could not read from kv")?
+                .as_string().ok_or("Could not convert kv-read value to String".to_owned())
+        })
+    }
+
+    fn put<'a>(&'a self, key: &'a str, value: &'a str) -> LocalBoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            kv::value(kv::AddressedNS::put(key, value))
+                .await.ok_or("Could not write to kv")?;
+            return Ok(());
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            kv::value(kv::AddressedNS::delete(key))
+                .await.ok_or("Could not delete from kv")?;
+            return Ok(());
+        })
+    }
+
+    fn keys(&self) -> LocalBoxFuture<'_, Result<Vec<String>, String>> {
+        Box::pin(async move {
+            let mut names  = vec![];
+            let mut cursor: Option<String> = None;
+            loop {
+                let listing = match &cursor {
+                    None    => kv::AddressedNS::list(),
+                    Some(c) => kv::AddressedNS::list_with(
+                        &JsValue::from_serde(&ListOptions { cursor: c.to_owned() })
+                            .ok().ok_or("Could not build the options to list keys with")?
+                    ),
+                };
+                let keys = kv::value(listing).await
+                    .ok_or("Could not list keys")?
+                    .into_serde::<KeysRust>()
+                    .ok().ok_or("Could not read the key object into a KeysRust")?;
+
+                names.extend(keys.keys.into_iter().map(|k| k.name));
+                if keys.list_complete { break; }
+                cursor = Some(keys.cursor.ok_or("KV listing is incomplete, but gave no cursor to continue from")?);
+            }
+            Ok(names)
+        })
+    }
+}
+
+// in-memory
+
+/// Keeps everything in a `HashMap`; nothing outlives the process.
+#[derive(Default)]
+pub struct Memory(RefCell<HashMap<String, String>>);
+
+impl Memory {
+    pub fn new() -> Memory {
+        Memory(RefCell::new(HashMap::new()))
+    }
+}
+
+impl Store for Memory {
+    fn get<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, Result<String, String>> {
+        let value = self.0.borrow().get(key).cloned()
+            .ok_or(format!("No value stored under '{}'", key));
+        Box::pin(future::ready(value))
+    }
+
+    fn put<'a>(&'a self, key: &'a str, value: &'a str) -> LocalBoxFuture<'a, Result<(), String>> {
+        self.0.borrow_mut().insert(key.to_owned(), value.to_owned());
+        Box::pin(future::ready(Ok(())))
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, Result<(), String>> {
+        self.0.borrow_mut().remove(key);
+        Box::pin(future::ready(Ok(())))
+    }
+
+    fn keys(&self) -> LocalBoxFuture<'_, Result<Vec<String>, String>> {
+        let keys = self.0.borrow().keys().cloned().collect::<Vec<String>>();
+        Box::pin(future::ready(Ok(keys)))
+    }
+}
+
+// local directory
+
+/// Stores each key as a file in a directory on disk.
+/// Only usable outside of a Worker, e.g. for local development.
+pub struct Directory(PathBuf);
+
+impl Directory {
+    pub fn new(root: PathBuf) -> Result<Directory, String> {
+        fs::create_dir_all(&root)
+            .ok().ok_or("Could not create the Directory store")?;
+        Ok(Directory(root))
+    }
+
+    fn file(&self, key: &str) -> Result<PathBuf, String> {
+        if key.is_empty() || key.contains('/') || key.contains('\\') || key.starts_with('.') {
+            return Err(format!("'{}' is not a valid key for a Directory store", key));
+        }
+        Ok(self.0.join(key))
+    }
+}
+
+impl Store for Directory {
+    fn get<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, Result<String, String>> {
+        let value = self.file(key).and_then(
+            |f| fs::read_to_string(f).ok().ok_or(format!("No value stored under '{}'", key))
+        );
+        Box::pin(future::ready(value))
+    }
+
+    fn put<'a>(&'a self, key: &'a str, value: &'a str) -> LocalBoxFuture<'a, Result<(), String>> {
+        let written = self.file(key).and_then(
+            |f| fs::write(f, value).ok().ok_or("Could not write to Directory store".to_owned())
+        );
+        Box::pin(future::ready(written))
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, Result<(), String>> {
+        let removed = self.file(key).and_then(
+            |f| match fs::remove_file(f) {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(_) => Err("Could not delete from Directory store".to_owned()),
+            }
+        );
+        Box::pin(future::ready(removed))
+    }
+
+    fn keys(&self) -> LocalBoxFuture<'_, Result<Vec<String>, String>> {
+        let keys = fs::read_dir(&self.0)
+            .ok().ok_or("Could not list Directory store".to_owned())
+            .map(|entries| entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| e.file_name().into_string().ok())
+                .filter(|k| !k.starts_with('.'))
+                .collect::<Vec<String>>()
+            );
+        Box::pin(future::ready(keys))
+    }
+}
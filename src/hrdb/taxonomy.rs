@@ -0,0 +1,88 @@
+use serde::{Serialize, Deserialize};
+use serde_json;
+use std::collections::BTreeMap;
+use crate::hrdb::utils::*;
+use crate::hrdb::fields::Value;
+
+/// The field pages are tagged with.
+pub const FIELD: &str = "tags";
+
+/// Every tag on the head of master, and the pages carrying it.
+/// Stored content-addressed, with the `taxonomy` key pointing at the latest one.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Taxonomy {
+    pub version: String, // the root it is up to date with
+    tags:        BTreeMap<String, BTreeMap<String, String>>, // tag -> id -> title
+}
+
+/// The tags in a page's `tags` field, which may be a list or a single tag.
+/// Tags are lowercased, so they can be used in routes.
+pub fn tags(raw: &str) -> Vec<String> {
+    let items = match Value::parse(raw) {
+        Ok(Value::List(items)) => items,
+        Ok(Value::Text(t))     => vec![t],
+        _                      => vec![],
+    };
+    let mut tags = items.into_iter()
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty() && !t.contains('/'))
+        .collect::<Vec<String>>();
+    tags.sort();
+    tags.dedup();
+    return tags;
+}
+
+impl Taxonomy {
+    pub async fn from(hash: &str) -> Result<Taxonomy, String> {
+        serde_json::from_str(&read(hash).await?)
+            .ok().ok_or("Could not deserialize Taxonomy".to_owned())
+    }
+
+    /// The latest taxonomy, if one has been built.
+    pub async fn current() -> Option<Taxonomy> {
+        Taxonomy::from(&read("taxonomy").await.ok()?).await.ok()
+    }
+
+    pub fn to_string(&self) -> Result<String, String> {
+        let serialized = serde_json::to_string(self)
+            .ok().ok_or("Could not serialize Taxonomy")?;
+        return Ok(serialized);
+    }
+
+    /// Writes the taxonomy, and makes it the latest.
+    pub async fn write(&self) -> Result<String, String> {
+        let address = write(&self.to_string()?).await?;
+        mutate("taxonomy", &address).await?;
+        return Ok(address);
+    }
+
+    pub fn insert(&mut self, id: String, title: String, tags: Vec<String>) {
+        self.remove(&id);
+        for tag in tags.into_iter() {
+            self.tags.entry(tag).or_default().insert(id.to_owned(), title.to_owned());
+        }
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        self.tags.retain(|_, pages| {
+            pages.remove(id);
+            !pages.is_empty()
+        });
+    }
+
+    /// Every tag, with how many pages carry it.
+    pub fn tags(&self) -> Vec<(String, usize)> {
+        self.tags.iter()
+            .map(|(tag, pages)| (tag.to_owned(), pages.len()))
+            .collect()
+    }
+
+    /// The id and title of every page with a tag, by title.
+    pub fn pages(&self, tag: &str) -> Vec<(String, String)> {
+        let mut pages = self.tags.get(tag)
+            .map(|p| p.iter().map(|(id, title)| (id.to_owned(), title.to_owned())).collect::<Vec<_>>())
+            .unwrap_or_default();
+        pages.sort_by_key(|a| a.1.to_lowercase());
+        return pages;
+    }
+}
@@ -1,8 +1,7 @@
-use js_sys::Date;
-use js_sys::Math;
+use cfg_if::cfg_if;
 use sha2::Sha256;
 use sha2::Digest;
-use crate::kv;
+use crate::hrdb::store;
 
 // helper functions
 // crypto
@@ -14,39 +13,78 @@ pub fn hash(string: &str) -> String {
         .collect::<String>()
 }
 
-pub fn stamp() -> Result<String, String> {
-    let stream = (0..32).map(|_| Math::random().to_string()).collect::<Vec<String>>().join("");
-    let pre_stamp = Date::now().to_string() + &stream;
-    Ok(hash(&pre_stamp))
+/// Whether a key could have been produced by `hash`.
+/// Bytes are written without leading zeros, so the length varies.
+pub fn is_address(key: &str) -> bool {
+    key.len() >= 32 && key.len() <= 64 && key.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+cfg_if! {
+    // js_sys can only be called from within a Worker,
+    // so outside of one we fall back to the OS for entropy
+    if #[cfg(target_arch = "wasm32")] {
+        use js_sys::Date;
+        use js_sys::Math;
+
+        pub fn stamp() -> Result<String, String> {
+            let stream = (0..32).map(|_| Math::random().to_string()).collect::<Vec<String>>().join("");
+            let pre_stamp = Date::now().to_string() + &stream;
+            Ok(hash(&pre_stamp))
+        }
+
+        /// Milliseconds since the epoch.
+        pub fn now() -> Result<u64, String> {
+            Ok(Date::now() as u64)
+        }
+    } else {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        pub fn stamp() -> Result<String, String> {
+            let mut stream = [0u8; 32];
+            getrandom::getrandom(&mut stream)
+                .ok().ok_or("Could not gather entropy for stamp")?;
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)
+                .ok().ok_or("System clock is set before the epoch")?;
+            let pre_stamp = now.as_millis().to_string() + &format!("{:?}", stream);
+            Ok(hash(&pre_stamp))
+        }
+
+        /// Milliseconds since the epoch.
+        pub fn now() -> Result<u64, String> {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)
+                .ok().ok_or("System clock is set before the epoch")?;
+            Ok(now.as_millis() as u64)
+        }
+    }
 }
 
 // key-value
 
 pub async fn write(value: &str) -> Result<String, String> {
     let key = hash(value);
-    kv::value(kv::AddressedNS::put(&key, value))
-        .await.ok_or("Could not write to kv")?;
+    store::current().put(&key, value).await?;
     return Ok(key);
 }
 
 pub async fn read(key: &str) -> Result<String, String> {
-    let x = kv::AddressedNS::get(key, "text");
-    kv::value(x)
-        .await.ok_or("Could not read from kv")?
-        .as_string().ok_or("Could not convert kv-read value to String".to_owned())
+    store::current().get(key).await
 }
 
 pub async fn mutate(key: &str, value: &str) -> Result<(), String> {
-    kv::value(kv::AddressedNS::put(key, value))
-        .await.ok_or("Could not mutate kv")?;
-    return Ok(());
+    store::current().put(key, value).await
+}
+
+pub async fn remove(key: &str) -> Result<(), String> {
+    store::current().delete(key).await
+}
+
+pub async fn keys() -> Result<Vec<String>, String> {
+    store::current().keys().await
 }
 
 pub async fn append(key: &str, value: Vec<String>) -> Result<(), String> {
     let contents = read(key).await? + "\n" + &value.join("\n");
-    kv::value(kv::AddressedNS::put(&key, &contents))
-        .await.ok_or("Could not push to kv")?;
-    return Ok(());
+    mutate(key, &contents).await
 }
 
 pub async fn push(key: &str, value: String) -> Result<(), String> {
@@ -68,7 +106,5 @@ pub async fn ensure(key: &str) -> Result<(), String> {
     if let Ok(_) = read(key).await {
         return Ok(());
     }
-    kv::value(kv::AddressedNS::put(&key, ""))
-        .await.ok_or("Could not kv-create new empty list")?;
-    return Ok(());
+    mutate(key, "").await
 }
@@ -31,6 +31,9 @@ extern "C" {
 
     #[wasm_bindgen(static_method_of = AddressedNS)]
     pub fn list() -> Promise;
+
+    #[wasm_bindgen(static_method_of = AddressedNS, js_name = list)]
+    pub fn list_with(options: &JsValue) -> Promise;
 }
 
 #[wasm_bindgen]
@@ -17,7 +17,7 @@ mod kv;
 mod logger;
 mod responder;
 mod route;
-mod hrdb;
+pub mod hrdb;
 mod template;
 mod renderer;
 mod auth;
@@ -43,8 +43,8 @@ pub async fn main(event: FetchEvent) -> Promise {
     };
     let path = Route::new(&url.path().to_lowercase());
     let method = request.method().to_lowercase();
-    let authed = auth::validate(&request).await;
-    let response = respond(request, path, method, authed).await;
+    let author = auth::identify(&request).await;
+    let response = respond(request, path, method, author).await;
 
     // if the response failed, we return an internal server error
     return match response {
@@ -57,7 +57,10 @@ pub async fn main(event: FetchEvent) -> Promise {
     };
 }
 
-pub async fn respond(request: Request, path: Route, method: String, authed: bool) -> Result<Response, String> {
+pub async fn respond(request: Request, path: Route, method: String, author: Option<String>) -> Result<Response, String> {
+    let authed = author.is_some();
+    let author = author.unwrap_or_default();
+
     match path.iter().nth(0) {
         // root -> redirect to home
         None => responder::redirect("/home")
@@ -82,12 +85,39 @@ pub async fn respond(request: Request, path: Route, method: String, authed: bool
             u     => Err(format!("'{}' method not allowed on /perma", u)),
         }
 
+        // diff -> compare a page between versions '/branch/before/after/id'
+        Some(d) if d == "diff" => match method.as_ref() {
+            "get" => renderer::diff::respond(path).await,
+            u     => Err(format!("'{}' method not allowed on /diff", u)),
+        }
+
+        // history -> list the versions in which a page changed '/branch/id'
+        Some(h) if h == "history" => match method.as_ref() {
+            "get" => renderer::history::respond(path).await,
+            u     => Err(format!("'{}' method not allowed on /history", u)),
+        }
+
+        // blame -> the version that last changed each line of a page '/branch/id'
+        Some(b) if b == "blame" => match method.as_ref() {
+            "get" => renderer::blame::respond(path).await,
+            u     => Err(format!("'{}' method not allowed on /blame", u)),
+        }
+
         // branches -> list all branches
         Some(b) if b == "branches" => match method.as_ref() {
             "get" => renderer::branches::respond(path).await,
             u     => Err(format!("'{}' method not allowed on /branches", u)),
         }
 
+        // branch -> delete, rename or protect a branch '/name/action'
+        Some(b) if b == "branch" => match method.as_ref() {
+            "get" if authed => renderer::branch::respond(path).await,
+            "post" if authed => renderer::branch::form(request, path).await,
+            "get" | "post" if !authed => responder::redirect("/auth")
+                .ok_or("Not authenticated; could not redirect".to_owned()),
+            u     => Err(format!("'{}' method not allowed on /branch", u)),
+        }
+
         // versions -> list all versions
         Some(v) if v == "versions" => match method.as_ref() {
             "get" => renderer::versions::respond(path).await,
@@ -105,7 +135,7 @@ pub async fn respond(request: Request, path: Route, method: String, authed: bool
         // edit -> load the editor '/branch/id'
         Some(e) if e == "edit" => match method.as_ref() {
             "get"  => renderer::edit::respond(path).await,
-            "post" if authed => renderer::edit::form(request, path).await,
+            "post" if authed => renderer::edit::form(request, path, author).await,
             "post" if !authed => responder::redirect("/auth")
                 .ok_or("Not authenticated; could not redirect".to_owned()),
             u     => Err(format!("'{}' method not allowed on /auth", u)),
@@ -113,7 +143,7 @@ pub async fn respond(request: Request, path: Route, method: String, authed: bool
 
         // create -> create new page
         Some(c) if c == "create" => match method.as_ref() {
-            "get" if authed => renderer::create::respond(path).await,
+            "get" if authed => renderer::create::respond(path, author).await,
             "get" if !authed => responder::redirect("/auth")
                 .ok_or("Not authenticated; could not redirect".to_owned()),
             u     => Err(format!("'{}' method not allowed on /create", u)),
@@ -121,17 +151,137 @@ pub async fn respond(request: Request, path: Route, method: String, authed: bool
 
         // delete -> remove page
         Some(d) if d == "delete" => match method.as_ref() {
-            "get" if authed => renderer::delete::respond(path).await,
+            "get" if authed => renderer::delete::respond(path, author).await,
             "get" if !authed => responder::redirect("/auth")
                 .ok_or("Not authenticated; could not redirect".to_owned()),
             u     => Err(format!("'{}' method not allowed on /delete", u)),
         },
 
-        // unimplemented
-        // search -> search master for query
-        Some(s) if s == "search" => renderer::search::respond(path).await,
-        Some(r) if r == "relocate" => renderer::relocate::respond(path).await,
-        // need to write fork and merge
+        // order -> move a page among its siblings, or sort its children '/branch/id/how'
+        Some(o) if o == "order" => match method.as_ref() {
+            "get" if authed => renderer::order::respond(path).await,
+            "post" if authed => renderer::order::form(path, author).await,
+            "get" | "post" if !authed => responder::redirect("/auth")
+                .ok_or("Not authenticated; could not redirect".to_owned()),
+            u     => Err(format!("'{}' method not allowed on /order", u)),
+        },
+
+        // restore -> bring back a page as it was '/branch/ver_no/id'
+        Some(r) if r == "restore" => match method.as_ref() {
+            "get" if authed => renderer::restore::respond(path).await,
+            "post" if authed => renderer::restore::form(path, author).await,
+            "get" | "post" if !authed => responder::redirect("/auth")
+                .ok_or("Not authenticated; could not redirect".to_owned()),
+            u     => Err(format!("'{}' method not allowed on /restore", u)),
+        },
+
+        // reset -> make an old version the head again '/branch/ver_no'
+        Some(r) if r == "reset" => match method.as_ref() {
+            "get" if authed => renderer::reset::respond(path).await,
+            "post" if authed => renderer::reset::form(path, author).await,
+            "get" | "post" if !authed => responder::redirect("/auth")
+                .ok_or("Not authenticated; could not redirect".to_owned()),
+            u     => Err(format!("'{}' method not allowed on /reset", u)),
+        },
+
+        // search -> search a branch for query '?q=query&branch=branch'
+        Some(s) if s == "search" => match method.as_ref() {
+            "get" => renderer::search::respond(request).await,
+            u     => Err(format!("'{}' method not allowed on /search", u)),
+        },
+
+        // tags -> list tags on master, or the pages with one '/tag'
+        Some(t) if t == "tags" => match method.as_ref() {
+            "get" => renderer::tags::respond(path).await,
+            u     => Err(format!("'{}' method not allowed on /tags", u)),
+        },
+
+        // relocate -> move a page under a new parent '/branch/id'
+        Some(r) if r == "relocate" => match method.as_ref() {
+            "get" if authed => renderer::relocate::respond(path).await,
+            "post" if authed => renderer::relocate::form(request, path, author).await,
+            "get" | "post" if !authed => responder::redirect("/auth")
+                .ok_or("Not authenticated; could not redirect".to_owned()),
+            u     => Err(format!("'{}' method not allowed on /relocate", u)),
+        },
+
+        // duplicate -> copy a page, or all of it, under another '/branch/id'
+        Some(d) if d == "duplicate" => match method.as_ref() {
+            "get" if authed => renderer::duplicate::respond(path).await,
+            "post" if authed => renderer::duplicate::form(request, path, author).await,
+            "get" | "post" if !authed => responder::redirect("/auth")
+                .ok_or("Not authenticated; could not redirect".to_owned()),
+            u     => Err(format!("'{}' method not allowed on /duplicate", u)),
+        },
+
+        // fork -> new branch from a version '/branch/ver_no'
+        Some(f) if f == "fork" => match method.as_ref() {
+            "get" if authed => renderer::fork::respond(path).await,
+            "post" if authed => renderer::fork::form(request, path).await,
+            "get" | "post" if !authed => responder::redirect("/auth")
+                .ok_or("Not authenticated; could not redirect".to_owned()),
+            u     => Err(format!("'{}' method not allowed on /fork", u)),
+        },
+
+        // release -> name a version '/branch/ver_no'
+        Some(r) if r == "release" => match method.as_ref() {
+            "get" if authed => renderer::release::respond(path).await,
+            "post" if authed => renderer::release::form(request, path, author).await,
+            "get" | "post" if !authed => responder::redirect("/auth")
+                .ok_or("Not authenticated; could not redirect".to_owned()),
+            u     => Err(format!("'{}' method not allowed on /release", u)),
+        },
+
+        // releases -> list every named version
+        // page tags already live at /tags
+        Some(r) if r == "releases" => match method.as_ref() {
+            "get" => renderer::releases::respond().await,
+            u     => Err(format!("'{}' method not allowed on /releases", u)),
+        },
+
+        // merge -> merge one branch into another '/from/into'
+        Some(m) if m == "merge" => match method.as_ref() {
+            "get" if authed => renderer::merge::respond(path).await,
+            "post" if authed => renderer::merge::form(path, author).await,
+            "get" | "post" if !authed => responder::redirect("/auth")
+                .ok_or("Not authenticated; could not redirect".to_owned()),
+            u     => Err(format!("'{}' method not allowed on /merge", u)),
+        },
+
+        // pick -> carry one page's change onto another branch '/branch/before/after/id/into'
+        Some(p) if p == "pick" => match method.as_ref() {
+            "get" if authed => renderer::pick::respond(path).await,
+            "post" if authed => renderer::pick::form(path, author).await,
+            "get" | "post" if !authed => responder::redirect("/auth")
+                .ok_or("Not authenticated; could not redirect".to_owned()),
+            u     => Err(format!("'{}' method not allowed on /pick", u)),
+        },
+
+        // gc -> preview or collect unreachable objects '?days=retention'
+        Some(g) if g == "gc" => match method.as_ref() {
+            "get" if authed => renderer::gc::respond(request).await,
+            "post" if authed => renderer::gc::form(request).await,
+            "get" | "post" if !authed => responder::redirect("/auth")
+                .ok_or("Not authenticated; could not redirect".to_owned()),
+            u     => Err(format!("'{}' method not allowed on /gc", u)),
+        },
+
+        // fsck -> check the integrity of every object
+        Some(f) if f == "fsck" => match method.as_ref() {
+            "get" if authed => renderer::fsck::respond().await,
+            "get" if !authed => responder::redirect("/auth")
+                .ok_or("Not authenticated; could not redirect".to_owned()),
+            u     => Err(format!("'{}' method not allowed on /fsck", u)),
+        },
+
+        // bundle -> export every branch, or import a bundle posted as the body
+        Some(b) if b == "bundle" => match method.as_ref() {
+            "get" if authed => renderer::bundle::respond().await,
+            "post" if authed => renderer::bundle::form(request).await,
+            "get" | "post" if !authed => responder::redirect("/auth")
+                .ok_or("Not authenticated; could not redirect".to_owned()),
+            u     => Err(format!("'{}' method not allowed on /bundle", u)),
+        },
 
         Some(d) if d == "dump" => match method.as_ref() {
             "get" if authed => renderer::dump::respond(path).await,
@@ -1,7 +1,19 @@
-use wasm_bindgen::prelude::*;
+use cfg_if::cfg_if;
 
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = console)]
-    pub fn log(s: &str);
+cfg_if! {
+    // console.log only exists inside a Worker,
+    // fall back to stderr everywhere else
+    if #[cfg(target_arch = "wasm32")] {
+        use wasm_bindgen::prelude::*;
+
+        #[wasm_bindgen]
+        extern "C" {
+            #[wasm_bindgen(js_namespace = console)]
+            pub fn log(s: &str);
+        }
+    } else {
+        pub fn log(s: &str) {
+            eprintln!("{}", s);
+        }
+    }
 }
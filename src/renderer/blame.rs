@@ -0,0 +1,22 @@
+use web_sys::Response;
+use crate::route::Route;
+use crate::responder;
+use crate::template;
+use crate::renderer::edit::{locate, branch_id};
+use crate::hrdb::{location::Location, controller};
+
+/// Shows which version last changed each line of a page, '/branch/id'.
+pub async fn respond(path: Route) -> Result<Response, String> {
+    let location = locate(path.clone()).await?;
+    let (branch, id) = branch_id(path).await?;
+    let lines = controller::blame(Location::from_branch(branch.clone()), id.clone()).await?;
+
+    let html = template::blame::render(
+        controller::title(&location).await?,
+        branch,
+        id,
+        lines,
+    ).await?;
+    responder::html(&html, 200)
+        .ok_or("Could not generate response blaming a page".to_owned())
+}
@@ -0,0 +1,60 @@
+use web_sys::{Response, Request};
+use crate::renderer::form;
+use crate::responder;
+use crate::template;
+use crate::route::Route;
+use crate::hrdb::{location::Location, controller};
+
+fn back() -> Result<Response, String> {
+    responder::redirect(&Route::over(vec!["branches".to_owned()]).to_string())
+        .ok_or("Changed the Branch, but could not redirect to the list of Branches".to_owned())
+}
+
+/// Manages a branch, '/branch/name/delete|protect|unprotect|rename'.
+/// Nothing is changed until the form this asks with is posted back.
+pub async fn respond(path: Route) -> Result<Response, String> {
+    let branch = path.iter().nth(1).ok_or("No branch specified")?.to_owned();
+    let action = path.iter().nth(2).ok_or("No action specified")?.to_owned();
+
+    let (title, message, submit) = match action.as_ref() {
+        "delete"    => ("Deleting", format!("This will remove {} from the list of branches; its versions are collected later.", branch), "Delete"),
+        "protect"   => ("Protecting", format!("This will only let {} change by merging into it.", branch), "Protect"),
+        "unprotect" => ("Unprotecting", format!("This will let anyone change {} directly again.", branch), "Unprotect"),
+        "rename"    => {
+            let html = template::rename::render(branch).await?;
+            return responder::html(&html, 200)
+                .ok_or("Could not load the rename form".to_owned());
+        },
+        a => return Err(format!("'{}' is not something that can be done to a Branch", a)),
+    };
+
+    let html = template::confirm::render(
+        title,
+        message,
+        path.clone().to_string(),
+        submit,
+        Route::over(vec!["branches".to_owned()]).to_string(),
+    ).await?;
+    responder::html(&html, 200)
+        .ok_or("Could not ask to change the Branch".to_owned())
+}
+
+pub async fn form(request: Request, path: Route) -> Result<Response, String> {
+    let branch = path.iter().nth(1).ok_or("No branch specified")?.to_owned();
+    let action = path.iter().nth(2).ok_or("No action specified")?.to_owned();
+    let location = Location::from_branch(branch.clone());
+
+    match action.as_ref() {
+        "delete"    => controller::delete_branch(location).await?,
+        "protect"   => controller::protect(location, true).await?,
+        "unprotect" => controller::protect(location, false).await?,
+        "rename"    => {
+            let form = form::parse(request).await?;
+            let name = form.get("name").as_string()
+                .ok_or("Could not retrieve new Branch name from request")?;
+            controller::rename_branch(location, Location::from_branch(name.trim().to_lowercase())).await?;
+        },
+        a => return Err(format!("'{}' is not something that can be done to a Branch", a)),
+    }
+    back()
+}
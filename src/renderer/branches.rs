@@ -2,18 +2,44 @@ use web_sys::Response;
 use crate::route::Route;
 use crate::responder;
 use crate::template;
+use crate::template::table::Item;
 use crate::hrdb::controller;
 
 pub async fn respond(path: Route) -> Result<Response, String> {
-    let branches = controller::branches().await?;
+    let branches  = controller::branches().await?;
+    let protected = controller::protected().await?;
+    let manage = |name: &str, action: &str| Item::link(
+        action[..1].to_uppercase() + &action[1..],
+        Route::over(vec!["branch".to_owned(), name.to_owned(), action.to_owned()]).to_string(),
+    );
+
     let names = branches.iter()
-        .map(|l| vec![l.branch()])
-        .collect::<Vec<Vec<String>>>();
+        .map(|l| {
+            let name = l.branch();
+            let guarded = protected.contains(&name);
+            let mut row = vec![
+                Item::text(name.to_owned()),
+                Item::link(
+                    "Versions".to_owned(),
+                    Route::over(vec!["versions".to_owned(), name.to_owned()]).to_string(),
+                ),
+                manage(&name, if guarded { "unprotect" } else { "protect" }),
+            ];
+            // master and protected branches stay put
+            if guarded || name == "master" {
+                row.extend(vec![Item::text(String::new()), Item::text(String::new())]);
+            } else {
+                row.extend(vec![manage(&name, "rename"), manage(&name, "delete")]);
+            }
+            row
+        })
+        .collect::<Vec<Vec<Item>>>();
 
-    let html = template::table::render(
+    let html = template::table::render_items(
         "Branches".to_owned(),
-        vec!["Name".to_owned()],
+        vec!["Name".to_owned(), "History".to_owned(), "Protection".to_owned(), String::new(), String::new()],
         names,
+        None,
     ).await?;
     responder::html(&html, 200)
         .ok_or("Could not generate response listing branches".to_owned())
@@ -0,0 +1,37 @@
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Response, Request};
+use crate::responder;
+use crate::template;
+use crate::hrdb::{controller, bundle::Bundle};
+
+/// Exports every branch as a bundle.
+pub async fn respond() -> Result<Response, String> {
+    let bundle = controller::export().await?;
+    responder::json(&bundle.to_string()?, 200)
+        .ok_or("Could not generate response containing the Bundle".to_owned())
+}
+
+/// Imports the bundle sent as the body of the request.
+pub async fn form(request: Request) -> Result<Response, String> {
+    let promise = request.text()
+        .ok().ok_or("Could not get body of request")?;
+    let body = JsFuture::from(promise).await
+        .ok().ok_or("Could not resolve body Promise")?
+        .as_string()
+        .ok_or("Body of request is not text")?;
+
+    let imported = controller::import(Bundle::from(&body)?).await?;
+    let mut rows = imported.branches.into_iter()
+        .map(|(name, outcome)| vec![name, outcome.to_owned()])
+        .collect::<Vec<Vec<String>>>();
+    rows.extend(imported.skipped.into_iter()
+        .map(|name| vec![format!("Release {}", name), "skipped, not on its Branch here".to_owned()]));
+
+    let html = template::table::render(
+        format!("Imported {} objects", imported.objects),
+        vec!["Name".to_owned(), "Outcome".to_owned()],
+        rows,
+    ).await?;
+    responder::html(&html, 200)
+        .ok_or("Could not generate response for the imported Bundle".to_owned())
+}
@@ -2,16 +2,17 @@ use std::collections::HashMap;
 use web_sys::Response;
 use crate::responder;
 use crate::route::Route;
-use crate::hrdb::controller;
+use crate::hrdb::{controller, commit::Signature};
 use crate::renderer::edit::locate;
 
-pub async fn respond(path: Route) -> Result<Response, String> {
+pub async fn respond(path: Route, author: String) -> Result<Response, String> {
     let parent = locate(path).await?;
     let child = controller::create(
         parent,
         "A New Mysterious Untitled Page".to_owned(),
         "".to_owned(),
-        HashMap::new()
+        HashMap::new(),
+        Signature::new(author, None),
     ).await?;
 
     responder::redirect(&format!("/edit/{}/{}", child.branch(), child.id().await?))
@@ -2,13 +2,13 @@ use web_sys::Response;
 use crate::route::Route;
 use crate::responder;
 use crate::renderer::edit::{locate, branch_id};
-use crate::hrdb::controller;
+use crate::hrdb::{controller, commit::Signature};
 
-pub async fn respond(path: Route) -> Result<Response, String> {
+pub async fn respond(path: Route, author: String) -> Result<Response, String> {
     let location = locate(path.clone()).await?;
     let parent   = location.back()?;
     let (branch, _) = branch_id(path).await?;
-    controller::delete(location).await?;
+    controller::delete(location, Signature::new(author, None)).await?;
     responder::redirect(
         &Route::over(vec![
             "perma".to_owned(),
@@ -0,0 +1,31 @@
+use web_sys::Response;
+use crate::responder;
+use crate::template;
+use crate::route::Route;
+use crate::renderer::perma::resolve;
+use crate::hrdb::controller;
+
+pub async fn respond(path: Route) -> Result<Response, String> {
+    let (b, before, after, id) = (
+        path.iter().nth(1).ok_or("No branch specified")?.to_owned(),
+        path.iter().nth(2).ok_or("No version number to compare from specified")?.to_owned(),
+        path.iter().nth(3).ok_or("No version number to compare to specified")?.to_owned(),
+        path.iter().nth(4).ok_or("No id specified")?.to_owned(),
+    );
+
+    let old = resolve(&b, &before).await?;
+    let new = resolve(&b, &after).await?;
+
+    // the page may not exist yet, or any more
+    let old = controller::locate_id(old, id.clone()).await.ok();
+    let new = controller::locate_id(new, id.clone()).await.ok();
+    let title = match (&new, &old) {
+        (Some(l), _) | (None, Some(l)) => controller::title(l).await?,
+        (None, None) => return Err("Page does not exist on either version".to_owned()),
+    };
+
+    let diff = controller::diff(old, new).await?;
+    let html = template::diff::render(title, b, before, after, id, diff).await?;
+    responder::html(&html, 200)
+        .ok_or("Could not generate response comparing versions".to_owned())
+}
@@ -1,36 +1,8 @@
-use serde::{Serialize, Deserialize};
-
-use std::collections::HashMap;
-
 use web_sys::Response;
-use js_sys::Object;
 
 use crate::responder;
 use crate::route::Route;
-use crate::kv;
-use crate::logger::log;
-use crate::hrdb::utils::read;
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct KeysRust {
-    list_complete: bool,
-    keys: Vec<KeyRust>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct KeyRust {
-    name: String,
-}
-
-pub fn to_key_list(keys_rust: KeysRust) -> Vec<String> {
-    let mut result = vec![];
-
-    for key_rust in keys_rust.keys.into_iter() {
-        result.push(key_rust.name);
-    }
-
-    return result;
-}
+use crate::hrdb::utils::{read, keys};
 
 pub async fn get_all_values(keys: Vec<String>) -> Result<Vec<(String, String)>, String> {
     let mut values = vec![];
@@ -44,16 +16,7 @@ pub async fn get_all_values(keys: Vec<String>) -> Result<Vec<(String, String)>,
 }
 
 pub async fn respond(_path: Route) -> Result<Response, String> {
-    let keys = kv::value(kv::AddressedNS::list()).await
-        .ok_or("Could not list keys")?;
-
-    // let key_object = Object::try_from(&keys)
-    //     .ok_or("convert jsvalue to object")?;
-
-    let keys_rust = keys.into_serde::<KeysRust>()
-        .ok().ok_or("Could not read the key object into a KeysRust")?;
-    let key_list = to_key_list(keys_rust);
-
+    let key_list = keys().await?;
     let values = get_all_values(key_list).await?;
 
     let debug = values.into_iter()
@@ -0,0 +1,68 @@
+use std::collections::HashSet;
+use web_sys::{Response, Request};
+use crate::renderer::form;
+use crate::responder;
+use crate::template;
+use crate::route::Route;
+use crate::renderer::edit::{locate, branch_id};
+use crate::renderer::relocate::destinations;
+use crate::hrdb::{controller, commit::Signature};
+
+async fn duplicate(path: Route, destination: String, deep: bool, author: String) -> Result<Response, String> {
+    let location = locate(path.clone()).await?;
+    let (branch, _) = branch_id(path).await?;
+    let to = controller::locate_id(
+        controller::head(location.clone()).await?,
+        destination,
+    ).await?;
+
+    let title = controller::title(&location).await?;
+    let copy  = controller::duplicate(
+        location,
+        to,
+        deep,
+        Signature::new(author, Some(format!("Duplicated {}", title))),
+    ).await?;
+
+    responder::redirect(
+        &Route::over(vec![
+            "perma".to_owned(),
+            branch,
+            "head".to_owned(),
+            copy.id().await?,
+        ]).to_string()
+    ).ok_or("Duplicated, but could not generate redirect to the copy".to_owned())
+}
+
+/// Asks where to copy a page, '/branch/id', and whether to copy everything beneath it.
+pub async fn respond(path: Route) -> Result<Response, String> {
+    let location = locate(path).await?;
+    let root     = controller::root(location.clone())?;
+
+    let destinations = destinations(root, &HashSet::new()).await?;
+
+    // the root page has no parent, so it is copied under itself
+    let parent = match location.back() {
+        Ok(p)  => p.id().await?,
+        Err(_) => location.id().await?,
+    };
+
+    let html = template::duplicate::render(
+        controller::title(&location).await?,
+        location.branch(),
+        location.id().await?,
+        parent,
+        destinations,
+    ).await?;
+    responder::html(&html, 200)
+        .ok_or("Could not load the duplicate form".to_owned())
+}
+
+pub async fn form(request: Request, path: Route, author: String) -> Result<Response, String> {
+    let form = form::parse(request).await?;
+    let destination = form.get("destination").as_string()
+        .ok_or("Could not retrieve destination from request")?;
+    let deep = form.get("children").as_string().is_some();
+
+    duplicate(path, destination, deep, author).await
+}
@@ -1,9 +1,10 @@
 use web_sys::{Response, Request};
 use crate::renderer::form;
+use crate::renderer::perma::numbered;
 use crate::responder;
 use crate::template;
 use crate::route::Route;
-use crate::hrdb::{location::Location, controller};
+use crate::hrdb::{location::Location, controller, commit::Signature, fields};
 use crate::logger::log;
 
 pub async fn branch_id(path: Route) -> Result<(String, String), String> {
@@ -23,27 +24,111 @@ pub async fn locate(path: Route) -> Result<Location, String> {
     return Ok(location);
 }
 
+/// The number of the head version of a location's branch.
+async fn head_ver_no(location: &Location) -> Result<usize, String> {
+    controller::versions(location.clone()).await?.len().checked_sub(1)
+        .ok_or("No versions exist on this branch yet".to_owned())
+}
+
 pub async fn respond(path: Route) -> Result<Response, String> {
     let location = locate(path).await?;
-    let (title, content, _) = controller::read(&location).await?;
+    if controller::protected().await?.contains(&location.branch()) {
+        return Err(format!("Branch {} is protected; fork it, edit the fork, then merge it back", location.branch()));
+    }
+    let (title, content, fields) = controller::read(&location).await?;
 
-    let html = template::edit::render(title, content, location.branch(), location.id().await?).await?;
+    let html = template::edit::render(
+        template::edit::Draft {
+            title,
+            content,
+            fields:  fields::block(&fields),
+            branch:  location.branch(),
+            id:      location.id().await?,
+            version: head_ver_no(&location).await?,
+        },
+        None,
+        None,
+    ).await?;
     responder::html(&html, 200)
         .ok_or("Could not load the editor".to_owned())
 }
 
-pub async fn form(request: Request, path: Route) -> Result<Response, String> {
+pub async fn form(request: Request, path: Route, author: String) -> Result<Response, String> {
     let form = form::parse(request).await?;
     let title = form.get("title").as_string()
         .ok_or("Could not retrieve new title from request")?;
     let edited = form.get("editor").as_string()
         .ok_or("Could not retrieve edited Page from request")?;
+    let message = form.get("message").as_string()
+        .map(|m| m.trim().to_owned())
+        .filter(|m| !m.is_empty());
+    let block = form.get("fields").as_string();
 
-    // get the hrdb location of the page
-    let location = locate(path).await?;
+    // get the hrdb location of the page, as it was when editing started
+    let (location, ver_no) = match form.get("version").as_string() {
+        Some(vn) => {
+            let (b, id) = branch_id(path).await?;
+            let (version, ver_no) = numbered(&b, &vn).await?;
+            (controller::locate_id(version, id).await?, ver_no)
+        },
+        None => {
+            let location = locate(path).await?;
+            let ver_no   = head_ver_no(&location).await?;
+            (location, ver_no)
+        },
+    };
+
+    // fields that can not be read are handed back to be fixed
+    let parsed = match block.as_ref().map(|b| fields::parse(b)) {
+        Some(Err(e)) => {
+            let html = template::edit::render(
+                template::edit::Draft {
+                    title,
+                    content: edited,
+                    fields:  block.unwrap_or_default(),
+                    branch:  location.branch(),
+                    id:      location.id().await?,
+                    version: ver_no,
+                },
+                None,
+                Some(e),
+            ).await?;
+            return responder::html(&html, 400)
+                .ok_or("Could not reload the editor after invalid fields".to_owned());
+        },
+        Some(Ok(f)) => Some(f),
+        None        => None,
+    };
 
     // update page with new information
-    controller::edit(location.clone(), Some(title), Some(edited), None).await?;
+    let saved = controller::edit(
+        location.clone(),
+        Some(title.clone()),
+        Some(edited.clone()),
+        parsed,
+        Signature::new(author, message),
+    ).await;
+
+    // someone else committed first, so hand the changes back against the new head
+    if let Err(e) = &saved {
+        if e == controller::STALE {
+            let html = template::edit::render(
+                template::edit::Draft {
+                    title,
+                    content: edited,
+                    fields:  block.unwrap_or_default(),
+                    branch:  location.branch(),
+                    id:      location.id().await?,
+                    version: head_ver_no(&location).await?,
+                },
+                Some(ver_no),
+                None,
+            ).await?;
+            return responder::html(&html, 409)
+                .ok_or("Could not reload the editor after a conflict".to_owned());
+        }
+    }
+    saved?;
 
     // redirect to head
     let head = Route::over(vec![
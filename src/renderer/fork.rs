@@ -0,0 +1,44 @@
+use web_sys::{Response, Request};
+use crate::renderer::form;
+use crate::renderer::perma::numbered;
+use crate::responder;
+use crate::template;
+use crate::route::Route;
+use crate::hrdb::{location::Location, controller};
+
+pub async fn branch_ver_no(path: &Route) -> Result<(String, String), String> {
+    return Ok((
+        path.iter().nth(1).ok_or("No branch specified")?.to_owned(),
+        path.iter().nth(2).ok_or("No version number specified")?.to_owned(),
+    ));
+}
+
+async fn fork(branch: String, vn: String, name: String) -> Result<Response, String> {
+    let (version, ver_no) = numbered(&branch, &vn).await?;
+    controller::fork(version, ver_no, Location::from_branch(name.clone())).await?;
+
+    responder::redirect(
+        &Route::over(vec![
+            "perma".to_owned(),
+            name,
+            "head".to_owned(),
+            "root".to_owned(),
+        ]).to_string()
+    ).ok_or("Forked, but could not generate redirect to the new Branch".to_owned())
+}
+
+pub async fn respond(path: Route) -> Result<Response, String> {
+    let (branch, vn) = branch_ver_no(&path).await?;
+    let html = template::fork::render(branch, vn).await?;
+    responder::html(&html, 200)
+        .ok_or("Could not load the fork form".to_owned())
+}
+
+pub async fn form(request: Request, path: Route) -> Result<Response, String> {
+    let (branch, vn) = branch_ver_no(&path).await?;
+    let form = form::parse(request).await?;
+    let name = form.get("name").as_string()
+        .ok_or("Could not retrieve new Branch name from request")?;
+
+    fork(branch, vn, name.trim().to_lowercase()).await
+}
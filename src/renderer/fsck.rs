@@ -0,0 +1,34 @@
+use web_sys::Response;
+use crate::responder;
+use crate::template;
+use crate::hrdb::controller;
+
+pub async fn respond() -> Result<Response, String> {
+    let report = controller::fsck().await?;
+
+    let title = if report.unread > 0 {
+        format!(
+            "Integrity of {} of {} objects — {} problems",
+            report.objects,
+            report.objects + report.unread,
+            report.problems.len(),
+        )
+    } else {
+        format!(
+            "Integrity of {} objects — {} problems",
+            report.objects,
+            report.problems.len(),
+        )
+    };
+    let rows = report.problems.into_iter()
+        .map(|p| vec![p.kind.to_owned(), p.key, p.detail])
+        .collect::<Vec<Vec<String>>>();
+
+    let html = template::table::render(
+        title,
+        vec!["Problem".to_owned(), "Key".to_owned(), "Detail".to_owned()],
+        rows,
+    ).await?;
+    responder::html(&html, 200)
+        .ok_or("Could not generate integrity report".to_owned())
+}
@@ -0,0 +1,40 @@
+use url::Url;
+use web_sys::{Response, Request};
+use crate::renderer::form;
+use crate::responder;
+use crate::template;
+use crate::hrdb::controller;
+
+/// An empty or missing retention period keeps every version.
+fn days(value: &str) -> Result<Option<u64>, String> {
+    match value.trim() {
+        "" => Ok(None),
+        d  => Ok(Some(d.parse::<u64>().ok().ok_or("Retention period must be a whole number of days")?)),
+    }
+}
+
+async fn collect(days: Option<u64>, dry_run: bool) -> Result<Response, String> {
+    let report = controller::gc(days, dry_run).await?;
+    let html = template::gc::render(days, report).await?;
+    responder::html(&html, 200)
+        .ok_or("Could not generate garbage collection report".to_owned())
+}
+
+/// Reports what would be collected, '?days=retention'.
+pub async fn respond(request: Request) -> Result<Response, String> {
+    let url = Url::parse(&request.url())
+        .ok().ok_or("Could not parse gc url")?;
+    let retention = url.query_pairs()
+        .find(|(key, _)| key == "days")
+        .map(|(_, value)| value.into_owned())
+        .unwrap_or_default();
+
+    collect(days(&retention)?, true).await
+}
+
+pub async fn form(request: Request) -> Result<Response, String> {
+    let form = form::parse(request).await?;
+    let retention = form.get("days").as_string().unwrap_or_default();
+
+    collect(days(&retention)?, false).await
+}
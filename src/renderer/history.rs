@@ -0,0 +1,66 @@
+use web_sys::Response;
+use crate::route::Route;
+use crate::responder;
+use crate::template;
+use crate::template::table::Item;
+use crate::renderer::edit::branch_id;
+use crate::hrdb::{location::Location, controller, commit::Commit};
+
+/// Lists every version in which a page changed, newest first, '/branch/id'.
+pub async fn respond(path: Route) -> Result<Response, String> {
+    let (branch, id) = branch_id(path).await?;
+    let history = controller::history(Location::from_branch(branch.clone()), id.clone()).await?;
+    let commits = controller::commits(Location::from_branch(branch.clone())).await?;
+    let (_, latest) = history.last().ok_or("This Page has never existed on this Branch")?;
+    let title = controller::title(latest).await?;
+
+    let mut rows = vec![];
+    for (i, (ver_no, location)) in history.iter().enumerate().rev() {
+        let (when, author, message) = Commit::summary(commits.get(*ver_no).cloned().flatten().as_deref()).await?;
+
+        let compare = match i {
+            0 => Item::text("Added".to_owned()),
+            _ => Item::link("Compare".to_owned(), Route::over(vec![
+                "diff".to_owned(),
+                branch.clone(),
+                history[i - 1].0.to_string(),
+                ver_no.to_string(),
+                id.clone(),
+            ]).to_string()),
+        };
+
+        let perma = Route::over(vec![
+            "perma".to_owned(),
+            branch.clone(),
+            ver_no.to_string(),
+            id.clone(),
+        ]).to_string();
+
+        rows.push(vec![
+            Item::text(ver_no.to_string()),
+            Item::text(controller::title(location).await?),
+            Item::text(when),
+            Item::text(author),
+            Item::text(message),
+            compare,
+            Item::link(perma.clone(), perma),
+        ]);
+    }
+
+    let html = template::table::render_items(
+        format!("History of {}", title),
+        vec![
+            "Version".to_owned(),
+            "Title".to_owned(),
+            "When".to_owned(),
+            "Author".to_owned(),
+            "Message".to_owned(),
+            "Changes".to_owned(),
+            "Permalink".to_owned(),
+        ],
+        rows,
+        None,
+    ).await?;
+    responder::html(&html, 200)
+        .ok_or("Could not generate response listing the history of a page".to_owned())
+}
@@ -0,0 +1,73 @@
+use web_sys::Response;
+use crate::route::Route;
+use crate::responder;
+use crate::template;
+use crate::hrdb::{location::Location, controller, commit::Signature};
+
+/// Lists what would keep one branch from merging into another,
+/// or asks before merging if nothing would, '/from/into'.
+pub async fn respond(path: Route) -> Result<Response, String> {
+    let (from, into) = (
+        path.iter().nth(1).ok_or("No Branch to merge from specified")?.to_owned(),
+        path.iter().nth(2).ok_or("No Branch to merge into specified")?.to_owned(),
+    );
+
+    let conflicts = controller::merge_conflicts(
+        Location::from_branch(from.clone()),
+        Location::from_branch(into.clone()),
+    ).await?;
+
+    if conflicts.is_empty() {
+        let html = template::confirm::render(
+            "Merging",
+            format!("This will merge the head of {} into {}, as a new version of {}.", from, into, into),
+            path.clone().to_string(),
+            "Merge",
+            Route::over(vec!["perma".to_owned(), from, "head".to_owned(), "root".to_owned()]).to_string(),
+        ).await?;
+        return responder::html(&html, 200)
+            .ok_or("Could not ask to merge the Branch".to_owned());
+    }
+
+    conflicted(conflicts, from, into).await
+}
+
+pub async fn form(path: Route, author: String) -> Result<Response, String> {
+    let (from, into) = (
+        path.iter().nth(1).ok_or("No Branch to merge from specified")?.to_owned(),
+        path.iter().nth(2).ok_or("No Branch to merge into specified")?.to_owned(),
+    );
+
+    let conflicts = controller::merge(
+        Location::from_branch(from.clone()),
+        Location::from_branch(into.clone()),
+        Signature::new(author, Some(format!("Merged {} into {}", from, into))),
+    ).await?;
+
+    if conflicts.is_empty() {
+        return responder::redirect(
+            &Route::over(vec![
+                "perma".to_owned(),
+                into,
+                "head".to_owned(),
+                "root".to_owned(),
+            ]).to_string()
+        ).ok_or("Merged, but could not generate redirect to merged head".to_owned());
+    }
+
+    conflicted(conflicts, from, into).await
+}
+
+async fn conflicted(conflicts: Vec<controller::Conflict>, from: String, into: String) -> Result<Response, String> {
+    let rows = conflicts.into_iter()
+        .map(|c| vec![c.title, c.id, c.reason])
+        .collect::<Vec<Vec<String>>>();
+
+    let html = template::table::render(
+        format!("Conflicts merging {} into {}", from, into),
+        vec!["Page".to_owned(), "Id".to_owned(), "Reason".to_owned()],
+        rows,
+    ).await?;
+    responder::html(&html, 409)
+        .ok_or("Could not generate response listing merge conflicts".to_owned())
+}
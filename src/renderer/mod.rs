@@ -1,14 +1,31 @@
 pub mod auth;
 pub mod static_;
 pub mod branches;
+pub mod branch;
 pub mod versions;
 pub mod search;
+pub mod tags;
 pub mod perma;
 pub mod shorthand;
 pub mod create;
 pub mod edit;
 pub mod delete;
+pub mod order;
 pub mod relocate;
+pub mod duplicate;
+pub mod merge;
+pub mod pick;
+pub mod fork;
+pub mod release;
+pub mod releases;
+pub mod diff;
+pub mod history;
+pub mod blame;
+pub mod restore;
+pub mod reset;
+pub mod gc;
+pub mod fsck;
+pub mod bundle;
 
 mod form;
 mod page;
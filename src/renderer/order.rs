@@ -0,0 +1,64 @@
+use web_sys::Response;
+use crate::route::Route;
+use crate::responder;
+use crate::template;
+use crate::renderer::edit::{locate, branch_id};
+use crate::hrdb::{controller, commit::Signature};
+
+/// Asks before reordering pages, '/branch/id/how'.
+/// 'up' and 'down' move the page among its siblings,
+/// 'title' and 'date' sort its children.
+pub async fn respond(path: Route) -> Result<Response, String> {
+    let location = locate(path.clone()).await?;
+    let how = path.iter().nth(3).ok_or("No ordering specified")?.to_owned();
+    let (branch, id) = branch_id(path.clone()).await?;
+
+    let title = controller::title(&location).await?;
+    let message = match how.as_ref() {
+        "up" | "down"    => format!("This will move {} {} among its siblings, as a new version.", title, how),
+        "title" | "date" => format!("This will sort the children of {} by {}, as a new version.", title, how),
+        u => return Err(format!("Can not order Pages by '{}'", u)),
+    };
+
+    let html = template::confirm::render(
+        "Reordering",
+        message,
+        path.to_string(),
+        "Reorder",
+        Route::over(vec!["perma".to_owned(), branch, "head".to_owned(), id]).to_string(),
+    ).await?;
+    responder::html(&html, 200)
+        .ok_or("Could not ask to reorder the Pages".to_owned())
+}
+
+pub async fn form(path: Route, author: String) -> Result<Response, String> {
+    let location = locate(path.clone()).await?;
+    let how = path.iter().nth(3).ok_or("No ordering specified")?.to_owned();
+    let (branch, id) = branch_id(path).await?;
+
+    // moved pages are seen among their siblings, sorted ones among their children
+    let shown = match how.as_ref() {
+        "up" | "down" => {
+            let parent = location.back()?;
+            let message = format!("Moved {} {}", controller::title(&location).await?, how);
+            controller::shift(location, how == "up", Signature::new(author, Some(message))).await?;
+            parent.id().await?
+        },
+        "title" | "date" => {
+            let field = if how == "date" { Some("date".to_owned()) } else { None };
+            let message = format!("Sorted {} by {}", controller::title(&location).await?, how);
+            controller::sort(location, field, Signature::new(author, Some(message))).await?;
+            id
+        },
+        u => return Err(format!("Can not order Pages by '{}'", u)),
+    };
+
+    responder::redirect(
+        &Route::over(vec![
+            "perma".to_owned(),
+            branch,
+            "head".to_owned(),
+            shown,
+        ]).to_string()
+    ).ok_or("Reordered, but could not generate redirect".to_owned())
+}
@@ -32,19 +32,20 @@ pub async fn shortify(location: Location) -> Result<Option<Response>, String> {
     };
 }
 
-pub async fn remap(location: Location) -> Result<Location, String> {
+pub async fn remap(location: Location, ver_no: usize) -> Result<(Location, usize), String> {
     // find and load the page location
     // try to find page on most recent version
     let master = Location::from_branch("master".to_owned());
+    let versions = controller::versions(master.clone()).await?.len();
     let head = controller::head(master).await?;
 
     match controller::locate_id(head, location.id().await?.clone()).await {
-        Ok(l) => Ok(l),
-        Err(_) => Ok(location),
+        Ok(l) => Ok((l, versions - 1)),
+        Err(_) => Ok((location, ver_no)),
     }
 }
 
-pub async fn render(location: Location) -> Result<Response, String> {
+pub async fn render(location: Location, ver_no: usize) -> Result<Response, String> {
     let parent = match location.back() {
         Ok(p)  => p.id().await?,
         Err(_) => location.id().await?,
@@ -57,19 +58,25 @@ pub async fn render(location: Location) -> Result<Response, String> {
 
     // redirect to head if on most recent version
     // redirect to root if at page root.
-    let is_head = controller::head(location.clone()).await?.version()? == location.version()?;
+    let is_head = controller::versions(location.clone()).await?.len() == ver_no + 1;
     let is_root = controller::root(location.clone())?.path()?          == location.path()?;
 
-    let (title, content, _) = controller::read(&location).await?;
+    let (title, content, fields) = controller::read(&location).await?;
+    let mut fields = fields.into_iter().collect::<Vec<(String, String)>>();
+    fields.sort();
+
     let html = template::page::render(
         title,
         content,
-        location.branch(),
-        location.ver_no().await?, // have them pass value in?
-        location.id().await?,
-        is_head,
-        is_root,
-        parent,
+        fields,
+        template::page::Place {
+            branch:  location.branch(),
+            vn:      ver_no,
+            iden:    location.id().await?,
+            is_head,
+            is_root,
+            parent,
+        },
         children,
     ).await?;
 
@@ -7,6 +7,34 @@ use crate::renderer::page;
 use crate::hrdb::{location::Location, controller};
 use crate::logger::log;
 
+/// Resolves a version number, 'head', or the name of a release to a version of a branch.
+pub async fn resolve(b: &str, vn: &str) -> Result<Location, String> {
+    Ok(numbered(b, vn).await?.0)
+}
+
+/// Resolves a version like `resolve`, along with its version number.
+/// Resetting brings back old roots, so the number can not be found from the root alone.
+pub async fn numbered(b: &str, vn: &str) -> Result<(Location, usize), String> {
+    let branch = Location::from_branch(b.to_owned());
+    let versions = controller::versions(branch).await?;
+
+    let ver_no = if vn == "head" {
+        versions.len().checked_sub(1).ok_or("No versions exist on this branch yet")?
+    } else if let Ok(ver_no) = vn.parse::<usize>() {
+        ver_no
+    } else {
+        let version = controller::released(b, vn).await?;
+        let ver_no  = version.ver_no().await?;
+        return Ok((version, ver_no));
+    };
+    let version = versions.get(ver_no).ok_or("Version with that number does not exist")?;
+
+    if version.version()? == controller::PRUNED {
+        return Err(format!("Version {} of {} was removed by garbage collection", vn, b));
+    }
+    return Ok((version.to_owned(), ver_no));
+}
+
 pub async fn respond(path: Route) -> Result<Response, String> {
     let (b, vn, id) = (
         path.iter().nth(1).ok_or("No branch specified")?,
@@ -14,18 +42,8 @@ pub async fn respond(path: Route) -> Result<Response, String> {
         path.iter().nth(3).ok_or("No id specified")?,
     );
 
-    let branch = Location::from_branch(b.to_owned());
-    let versions = controller::versions(branch).await?;
-
-    let version = if vn == "head" {
-        // redirect to shorthand page
-        versions.iter().last().ok_or("No versions exist on this branch yet")?
-    } else {
-        let ver_no = vn.parse::<usize>()
-            .ok().ok_or("Version number was not a number")?;
-        versions.iter()
-            .nth(ver_no).ok_or("Version with that number does not exist")?
-    };
+    // head redirects to shorthand page
+    let (version, ver_no) = numbered(b, vn).await?;
 
     let location = if id == "root" {
         controller::root(version.clone())?
@@ -35,6 +53,6 @@ pub async fn respond(path: Route) -> Result<Response, String> {
 
     return match page::shortify(location.clone()).await? {
         Some(r) if vn == "head" => Ok(r),
-        _ => page::render(location).await,
+        _ => page::render(location, ver_no).await,
     };
 }
@@ -0,0 +1,71 @@
+use web_sys::Response;
+use crate::route::Route;
+use crate::responder;
+use crate::template;
+use crate::renderer::perma::resolve;
+use crate::hrdb::{location::Location, controller, commit::Signature};
+
+/// Asks before carrying one page's change onto another branch, '/branch/before/after/id/into'.
+pub async fn respond(path: Route) -> Result<Response, String> {
+    let (b, before, after, id, into) = (
+        path.iter().nth(1).ok_or("No branch specified")?,
+        path.iter().nth(2).ok_or("No earlier version number specified")?,
+        path.iter().nth(3).ok_or("No later version number specified")?,
+        path.iter().nth(4).ok_or("No id specified")?,
+        path.iter().nth(5).ok_or("No Branch to cherry-pick onto specified")?,
+    );
+
+    let source = controller::locate_id(resolve(b, after).await?, id.to_owned()).await?;
+    let title  = controller::title(&source).await?;
+    let html = template::confirm::render(
+        "Cherry-picking",
+        format!("This will carry the change made to {} between versions {} and {} of {} onto {}, as a new version.", title, before, after, b, into),
+        path.clone().to_string(),
+        "Cherry-pick",
+        Route::over(vec!["perma".to_owned(), b.to_owned(), after.to_owned(), id.to_owned()]).to_string(),
+    ).await?;
+    responder::html(&html, 200)
+        .ok_or("Could not ask to cherry-pick the change".to_owned())
+}
+
+pub async fn form(path: Route, author: String) -> Result<Response, String> {
+    let (b, before, after, id, into) = (
+        path.iter().nth(1).ok_or("No branch specified")?,
+        path.iter().nth(2).ok_or("No earlier version number specified")?,
+        path.iter().nth(3).ok_or("No later version number specified")?,
+        path.iter().nth(4).ok_or("No id specified")?,
+        path.iter().nth(5).ok_or("No Branch to cherry-pick onto specified")?.to_owned(),
+    );
+
+    let source = controller::locate_id(resolve(b, after).await?, id.to_owned()).await?;
+    let title  = controller::title(&source).await?;
+    let conflicts = controller::cherry_pick(
+        source,
+        resolve(b, before).await?,
+        Location::from_branch(into.clone()),
+        Signature::new(author, Some(format!("Cherry-picked {} from {}", title, b))),
+    ).await?;
+
+    if conflicts.is_empty() {
+        return responder::redirect(
+            &Route::over(vec![
+                "perma".to_owned(),
+                into,
+                "head".to_owned(),
+                id.to_owned(),
+            ]).to_string()
+        ).ok_or("Cherry-picked, but could not generate redirect to the Page".to_owned());
+    }
+
+    let rows = conflicts.into_iter()
+        .map(|c| vec![c.title, c.id, c.reason])
+        .collect::<Vec<Vec<String>>>();
+
+    let html = template::table::render(
+        format!("Conflicts cherry-picking {} onto {}", title, into),
+        vec!["Page".to_owned(), "Id".to_owned(), "Reason".to_owned()],
+        rows,
+    ).await?;
+    responder::html(&html, 409)
+        .ok_or("Could not generate response listing cherry-pick conflicts".to_owned())
+}
@@ -0,0 +1,38 @@
+use web_sys::{Response, Request};
+use crate::renderer::form;
+use crate::renderer::fork::branch_ver_no;
+use crate::renderer::perma::resolve;
+use crate::responder;
+use crate::template;
+use crate::route::Route;
+use crate::hrdb::controller;
+
+async fn release(branch: String, vn: String, name: String, author: String) -> Result<Response, String> {
+    let version = resolve(&branch, &vn).await?;
+    controller::release(version, name.clone(), author).await?;
+
+    responder::redirect(
+        &Route::over(vec![
+            "perma".to_owned(),
+            branch,
+            name,
+            "root".to_owned(),
+        ]).to_string()
+    ).ok_or("Released, but could not generate redirect to the release".to_owned())
+}
+
+pub async fn respond(path: Route) -> Result<Response, String> {
+    let (branch, vn) = branch_ver_no(&path).await?;
+    let html = template::release::render(branch, vn).await?;
+    responder::html(&html, 200)
+        .ok_or("Could not load the release form".to_owned())
+}
+
+pub async fn form(request: Request, path: Route, author: String) -> Result<Response, String> {
+    let (branch, vn) = branch_ver_no(&path).await?;
+    let form = form::parse(request).await?;
+    let name = form.get("name").as_string()
+        .ok_or("Could not retrieve Release name from request")?;
+
+    release(branch, vn, name.trim().to_lowercase(), author).await
+}
@@ -0,0 +1,47 @@
+use web_sys::Response;
+use crate::route::Route;
+use crate::responder;
+use crate::template;
+use crate::template::table::Item;
+use crate::hrdb::{location::Location, release::Releases};
+
+/// Lists every release, newest first.
+pub async fn respond() -> Result<Response, String> {
+    let releases = Releases::read().await?;
+    let mut releases = releases.iter().collect::<Vec<_>>();
+    releases.sort_by_key(|r| std::cmp::Reverse(r.1.timestamp));
+
+    let mut rows = vec![];
+    for (name, release) in releases.into_iter() {
+        let version = Location::from_branch_and_version(release.branch.to_owned(), release.version.to_owned());
+        let ver_no  = match version.ver_no().await {
+            Ok(n)  => n.to_string(),
+            Err(_) => "Missing".to_owned(),
+        };
+        rows.push(vec![
+            Item::link(
+                name.to_owned(),
+                Route::over(vec!["perma".to_owned(), release.branch.to_owned(), name.to_owned(), "root".to_owned()]).to_string(),
+            ),
+            Item::text(release.branch.to_owned()),
+            Item::text(ver_no),
+            Item::text(release.version.to_owned()),
+            Item::text(release.author.chars().take(8).collect::<String>()),
+        ]);
+    }
+
+    let html = template::table::render_items(
+        "Releases".to_owned(),
+        vec![
+            "Release".to_owned(),
+            "Branch".to_owned(),
+            "Version".to_owned(),
+            "Root".to_owned(),
+            "Author".to_owned(),
+        ],
+        rows,
+        None,
+    ).await?;
+    responder::html(&html, 200)
+        .ok_or("Could not generate response listing releases".to_owned())
+}
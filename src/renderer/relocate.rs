@@ -1,6 +1,74 @@
-use web_sys::Response;
+use std::collections::HashSet;
+use web_sys::{Response, Request};
+use crate::renderer::form;
+use crate::responder;
+use crate::template;
 use crate::route::Route;
+use crate::renderer::edit::{locate, branch_id};
+use crate::hrdb::{location::Location, controller, commit::Signature};
+
+async fn relocate(path: Route, destination: String, author: String) -> Result<Response, String> {
+    let location = locate(path.clone()).await?;
+    let (branch, id) = branch_id(path).await?;
+    let to = controller::locate_id(
+        controller::head(location.clone()).await?,
+        destination,
+    ).await?;
+
+    controller::relocate(location, to, Signature::new(author, None)).await?;
+
+    responder::redirect(
+        &Route::over(vec![
+            "perma".to_owned(),
+            branch,
+            "head".to_owned(),
+            id,
+        ]).to_string()
+    ).ok_or("Moved, but could not generate redirect to the moved Page".to_owned())
+}
+
+/// Every page under a root, parents before their children and children in order,
+/// as a title indented by depth and an id, leaving out the pages at `exclude`d addresses.
+pub async fn destinations(root: Location, exclude: &HashSet<String>) -> Result<Vec<(String, String)>, String> {
+    let mut destinations = vec![];
+    let mut stack = vec![root];
+    while let Some(l) = stack.pop() {
+        stack.extend(controller::children(l.clone()).await?.into_iter().rev());
+        if exclude.contains(&l.end()?) { continue; }
+        let depth = l.path()?.len() - 1;
+        let title = "— ".repeat(depth) + &controller::title(&l).await?;
+        destinations.push((title, l.id().await?));
+    }
+    return Ok(destinations);
+}
 
 pub async fn respond(path: Route) -> Result<Response, String> {
-    Err("Moving pages is not yet implemented".to_owned())
+    let location = locate(path).await?;
+    let root     = controller::root(location.clone())?;
+
+    // can not move a page under itself
+    let mut exclude = HashSet::new();
+    for l in controller::descendants(location.clone()).await? {
+        exclude.insert(l.end()?);
+    }
+    exclude.insert(location.back()?.end()?);
+
+    let destinations = destinations(root, &exclude).await?;
+
+    let html = template::relocate::render(
+        controller::title(&location).await?,
+        location.branch(),
+        location.id().await?,
+        destinations,
+    ).await?;
+    responder::html(&html, 200)
+        .ok_or("Could not load the move form".to_owned())
+}
+
+pub async fn form(request: Request, path: Route, author: String) -> Result<Response, String> {
+    let form = form::parse(request).await?;
+    let destination = form.get("destination").as_string()
+        .ok_or("Could not retrieve destination from request")?;
+
+    relocate(path, destination, author).await
 }
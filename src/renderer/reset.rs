@@ -0,0 +1,46 @@
+use web_sys::Response;
+use crate::route::Route;
+use crate::responder;
+use crate::template;
+use crate::renderer::perma::resolve;
+use crate::hrdb::{controller, commit::Signature};
+
+/// Asks before resetting a branch, '/branch/ver_no'.
+pub async fn respond(path: Route) -> Result<Response, String> {
+    let (b, vn) = (
+        path.iter().nth(1).ok_or("No branch specified")?,
+        path.iter().nth(2).ok_or("No version number specified")?,
+    );
+
+    let html = template::confirm::render(
+        "Resetting",
+        format!("This will make version {} the head of {} again, as a new version.", vn, b),
+        path.clone().to_string(),
+        "Reset",
+        Route::over(vec!["perma".to_owned(), b.to_owned(), vn.to_owned(), "root".to_owned()]).to_string(),
+    ).await?;
+    responder::html(&html, 200)
+        .ok_or("Could not ask to reset the Branch".to_owned())
+}
+
+pub async fn form(path: Route, author: String) -> Result<Response, String> {
+    let (b, vn) = (
+        path.iter().nth(1).ok_or("No branch specified")?,
+        path.iter().nth(2).ok_or("No version number specified")?,
+    );
+
+    let version = resolve(b, vn).await?;
+    controller::reset(
+        version,
+        Signature::new(author, Some(format!("Reset to version {}", vn))),
+    ).await?;
+
+    responder::redirect(
+        &Route::over(vec![
+            "perma".to_owned(),
+            b.to_owned(),
+            "head".to_owned(),
+            "root".to_owned(),
+        ]).to_string()
+    ).ok_or("Reset, but could not generate redirect to the new head".to_owned())
+}
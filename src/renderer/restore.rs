@@ -0,0 +1,54 @@
+use web_sys::Response;
+use crate::route::Route;
+use crate::responder;
+use crate::template;
+use crate::renderer::perma::resolve;
+use crate::hrdb::{controller, commit::Signature};
+
+/// Asks before restoring a page, '/branch/ver_no/id'.
+pub async fn respond(path: Route) -> Result<Response, String> {
+    let (b, vn, id) = (
+        path.iter().nth(1).ok_or("No branch specified")?,
+        path.iter().nth(2).ok_or("No version number specified")?,
+        path.iter().nth(3).ok_or("No id specified")?,
+    );
+
+    let html = template::confirm::render(
+        "Restoring",
+        format!("This will bring back the page as it was in version {} of {}, as a new version.", vn, b),
+        path.clone().to_string(),
+        "Restore",
+        Route::over(vec!["perma".to_owned(), b.to_owned(), vn.to_owned(), id.to_owned()]).to_string(),
+    ).await?;
+    responder::html(&html, 200)
+        .ok_or("Could not ask to restore the Page".to_owned())
+}
+
+pub async fn form(path: Route, author: String) -> Result<Response, String> {
+    let (b, vn, id) = (
+        path.iter().nth(1).ok_or("No branch specified")?,
+        path.iter().nth(2).ok_or("No version number specified")?,
+        path.iter().nth(3).ok_or("No id specified")?,
+    );
+
+    let version = resolve(b, vn).await?;
+    let old = if id == "root" {
+        controller::root(version)?
+    } else {
+        controller::locate_id(version, id.to_owned()).await?
+    };
+
+    let restored = controller::restore(
+        old,
+        Signature::new(author, Some(format!("Restored from version {}", vn))),
+    ).await?;
+
+    responder::redirect(
+        &Route::over(vec![
+            "perma".to_owned(),
+            b.to_owned(),
+            "head".to_owned(),
+            restored.id().await?,
+        ]).to_string()
+    ).ok_or("Restored, but could not generate redirect to the Page".to_owned())
+}
@@ -1,12 +1,85 @@
-use web_sys::Response;
+use url::Url;
+use web_sys::{Response, Request};
 use crate::route::Route;
+use crate::responder;
+use crate::template;
+use crate::hrdb::{location::Location, controller, shorthand::Shorthand};
+use crate::hrdb::search::{tokenize, snippet};
+use crate::hrdb::utils::read;
 
-pub async fn respond(path: Route) -> Result<Response, String> {
-    // calculate filters for query
-    // load shorthand
-    // iterate through all pages
-    // try against all pages, giving a point for each filter that matches
-    // sort by points
-    // return all pages with score > 1 in score order
-    Err("Searching is not yet implemented".to_owned())
+/// How many results are shown for a single query.
+const RESULTS: usize = 20;
+
+/// Lists every page on master mentioning all of the words, '?mentions=words'.
+async fn mentioning(words: String) -> Result<Response, String> {
+    let table = Shorthand::read().await?;
+    let terms = tokenize(&words);
+
+    let mut results = vec![];
+    for location in controller::mentioning(&words).await? {
+        let id   = location.id().await?;
+        let link = match table.find(&id) {
+            Some(short) => Route::over(vec![short]).to_string(),
+            None => Route::over(vec![
+                "perma".to_owned(),
+                "master".to_owned(),
+                "head".to_owned(),
+                id,
+            ]).to_string(),
+        };
+        let (title, content, _) = controller::read(&location).await?;
+        results.push((title, link, snippet(&content, &terms)));
+    }
+
+    let html = template::search::render(words, "master".to_owned(), Some(results)).await?;
+    responder::html(&html, 200)
+        .ok_or("Could not generate response listing mentions".to_owned())
+}
+
+pub async fn respond(request: Request) -> Result<Response, String> {
+    let url = Url::parse(&request.url())
+        .ok().ok_or("Could not parse search url")?;
+    let (mut query, mut branch, mut mentions) = (String::new(), "master".to_owned(), String::new());
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "q"        => query    = value.trim().to_owned(),
+            "branch"   => branch   = value.trim().to_lowercase(),
+            "mentions" => mentions = value.trim().to_owned(),
+            _          => (),
+        }
+    }
+
+    if !mentions.is_empty() {
+        return mentioning(mentions).await;
+    }
+
+    if query.is_empty() {
+        let html = template::search::render(query, branch, None).await?;
+        return responder::html(&html, 200)
+            .ok_or("Could not load the search page".to_owned());
+    }
+
+    let hits  = controller::search(Location::from_branch(branch.clone()), &query).await?;
+    let terms = tokenize(&query);
+    let table = if branch == "master" { Some(Shorthand::read().await?) } else { None };
+
+    let mut results = vec![];
+    for hit in hits.into_iter().take(RESULTS) {
+        // link to the shorthand on master, otherwise to the head of the branch
+        let link = match table.as_ref().and_then(|t| t.find(&hit.id)) {
+            Some(short) => Route::over(vec![short]).to_string(),
+            None => Route::over(vec![
+                "perma".to_owned(),
+                branch.clone(),
+                "head".to_owned(),
+                hit.id.clone(),
+            ]).to_string(),
+        };
+        let text = read(&hit.doc.content).await?;
+        results.push((hit.doc.title, link, snippet(&text, &terms)));
+    }
+
+    let html = template::search::render(query, branch, Some(results)).await?;
+    responder::html(&html, 200)
+        .ok_or("Could not generate response for search".to_owned())
 }
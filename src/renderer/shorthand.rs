@@ -1,15 +1,17 @@
 use web_sys::Response;
 use crate::responder;
 use crate::template;
+use crate::route::Route;
 use crate::renderer::page;
-use crate::hrdb::{location::Location, controller, shorthand::Shorthand};
+use crate::hrdb::{location::Location, controller, shorthand::{Shorthand, Aliases}};
 
 pub async fn respond(short: &str) -> Result<Response, String>  {
     // look up the id-path
-    let (ver_no, id, _) = Shorthand::read()
-        .await?.unwrap().get(short)
-        .ok_or("Shorthand does not map to Page")?
-        .to_owned();
+    let table = Shorthand::read().await?;
+    let (ver_no, id, _) = match table.unwrap().get(short) {
+        Some(entry) => entry.to_owned(),
+        None        => return moved(short).await,
+    };
 
     // try to retrieve shorthand on master
     // if that fails, fall back to specified version.
@@ -20,7 +22,18 @@ pub async fn respond(short: &str) -> Result<Response, String>  {
         .ok_or("Shorthand mapped to Page, but Page version is not valid")?
         .to_owned();
     let specified = controller::locate_id(version, id.clone()).await?;
-    let location = page::remap(specified).await?;
+    let (location, ver_no) = page::remap(specified, ver_no).await?;
+
+    return page::render(location, ver_no).await;
+}
+
+/// Sends an old name on to the page's current shorthand.
+async fn moved(short: &str) -> Result<Response, String> {
+    let id = Aliases::read().await?.get(short).cloned()
+        .ok_or("Shorthand does not map to Page")?;
+    let current = Shorthand::read().await?.find(&id)
+        .ok_or("Shorthand used to map to a Page, but that Page no longer has one")?;
 
-    return page::render(location).await;
+    responder::moved(&Route::over(vec![current]).to_string())
+        .ok_or("Could not generate redirect to the current Shorthand".to_owned())
 }
@@ -0,0 +1,62 @@
+use web_sys::Response;
+use crate::route::Route;
+use crate::responder;
+use crate::template;
+use crate::template::table::Item;
+use crate::hrdb::{controller, shorthand::Shorthand};
+
+/// Lists every tag on master, '/tags', or every page with a tag, '/tags/tag'.
+pub async fn respond(path: Route) -> Result<Response, String> {
+    let taxonomy = controller::retag().await?;
+
+    let html = match path.iter().nth(1) {
+        None => {
+            let rows = taxonomy.tags().into_iter()
+                .map(|(tag, count)| vec![
+                    Item::link(tag.to_owned(), Route::over(vec!["tags".to_owned(), tag]).to_string()),
+                    Item::text(count.to_string()),
+                ])
+                .collect::<Vec<Vec<Item>>>();
+
+            template::table::render_items(
+                "Tags".to_owned(),
+                vec!["Tag".to_owned(), "Pages".to_owned()],
+                rows,
+                None,
+            ).await?
+        },
+        Some(tag) => {
+            let pages = taxonomy.pages(tag);
+            if pages.is_empty() {
+                return Err(format!("No Pages on master are tagged '{}'", tag));
+            }
+
+            // link to the shorthand, otherwise to the head of master
+            let table = Shorthand::read().await?;
+            let rows = pages.into_iter()
+                .map(|(id, title)| {
+                    let link = match table.find(&id) {
+                        Some(short) => Route::over(vec![short]).to_string(),
+                        None => Route::over(vec![
+                            "perma".to_owned(),
+                            "master".to_owned(),
+                            "head".to_owned(),
+                            id,
+                        ]).to_string(),
+                    };
+                    vec![Item::link(title, link)]
+                })
+                .collect::<Vec<Vec<Item>>>();
+
+            template::table::render_items(
+                format!("Tagged {}", tag),
+                vec!["Page".to_owned()],
+                rows,
+                None,
+            ).await?
+        },
+    };
+
+    responder::html(&html, 200)
+        .ok_or("Could not generate response listing tags".to_owned())
+}
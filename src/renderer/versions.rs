@@ -1,6 +1,87 @@
 use web_sys::Response;
 use crate::route::Route;
+use crate::responder;
+use crate::template;
+use crate::template::table::{Item, Pages};
+use crate::hrdb::{location::Location, controller, commit::Commit};
+
+/// How many versions are listed on each page, newest first.
+const PER_PAGE: usize = 25;
 
 pub async fn respond(path: Route) -> Result<Response, String> {
-    Err("Listing versions is not yet implemented".to_owned())
+    let branch = path.iter().nth(1).ok_or("No branch specified")?.to_owned();
+    let page = match path.iter().nth(2) {
+        Some(p) => p.parse::<usize>().ok().ok_or("Page number was not a number")?,
+        None    => 0,
+    };
+
+    let versions = controller::versions(Location::from_branch(branch.clone())).await?;
+    let commits  = controller::commits(Location::from_branch(branch.clone())).await?;
+    let start    = page * PER_PAGE;
+    if start >= versions.len() {
+        return Err("There are no versions on this page".to_owned());
+    }
+
+    let mut rows = vec![];
+    for ver_no in (0..versions.len()).rev().skip(start).take(PER_PAGE) {
+        let version = versions[ver_no].clone();
+        if version.version()? == controller::PRUNED {
+            rows.push(vec![
+                Item::text(ver_no.to_string()),
+                Item::text("Removed by garbage collection".to_owned()),
+            ]);
+            continue;
+        }
+        let changed = if ver_no == 0 || versions[ver_no - 1].version()? == controller::PRUNED {
+            "Initial version".to_owned()
+        } else {
+            let mut described = vec![];
+            for change in controller::changes(versions[ver_no - 1].clone(), version.clone()).await? {
+                described.push(format!("{} {}", change.verb(), controller::title(change.location()).await?));
+            }
+            if described.is_empty() { "No changes".to_owned() } else { described.join(", ") }
+        };
+
+        let (when, author, message) = Commit::summary(commits[ver_no].as_deref()).await?;
+
+        let perma = Route::over(vec![
+            "perma".to_owned(),
+            branch.clone(),
+            ver_no.to_string(),
+            "root".to_owned(),
+        ]).to_string();
+
+        rows.push(vec![
+            Item::text(ver_no.to_string()),
+            Item::text(version.version()?),
+            Item::text(when),
+            Item::text(author),
+            Item::text(message),
+            Item::text(changed),
+            Item::link(perma.clone(), perma),
+        ]);
+    }
+
+    let link = |p: usize| Route::over(vec!["versions".to_owned(), branch.clone(), p.to_string()]).to_string();
+    let pages = Pages {
+        newer: if page > 0 { Some(link(page - 1)) } else { None },
+        older: if start + PER_PAGE < versions.len() { Some(link(page + 1)) } else { None },
+    };
+
+    let html = template::table::render_items(
+        format!("Versions of {}", branch),
+        vec![
+            "Version".to_owned(),
+            "Root".to_owned(),
+            "When".to_owned(),
+            "Author".to_owned(),
+            "Message".to_owned(),
+            "Changes".to_owned(),
+            "Permalink".to_owned(),
+        ],
+        rows,
+        if pages.newer.is_none() && pages.older.is_none() { None } else { Some(pages) },
+    ).await?;
+    responder::html(&html, 200)
+        .ok_or("Could not generate response listing versions".to_owned())
 }
@@ -23,6 +23,10 @@ pub fn css(c: &str, status: u16) -> Option<Response> {
     content(c, "text/css; charset=utf-8", status)
 }
 
+pub fn json(c: &str, status: u16) -> Option<Response> {
+    content(c, "application/json; charset=utf-8", status)
+}
+
 pub fn plain(c: &str, status: u16) -> Option<Response> {
     content(c, "text/plain; charset=utf-8", status)
 }
@@ -33,6 +37,13 @@ pub fn redirect(url: &str) -> Option<Response> {
     respond("", headers, 302)
 }
 
+/// A permanent redirect, for addresses that have moved for good.
+pub fn moved(url: &str) -> Option<Response> {
+    let headers: Headers = Headers::new().ok()?;
+    headers.set("location", url).ok()?;
+    respond("", headers, 301)
+}
+
 pub fn cookie(c: Cookie, redirect: &str) -> Option<Response> {
     let mut headers: Headers = Headers::new().ok()?;
     headers.set("location", redirect).ok()?;
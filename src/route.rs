@@ -1,14 +1,16 @@
 use std::slice::Iter;
 use std::vec::IntoIter;
+use url::percent_encoding::{percent_decode, utf8_percent_encode, PATH_SEGMENT_ENCODE_SET};
 
 #[derive(Debug, Clone)]
 pub struct Route(Vec<String>);
 
 impl Route {
+    /// Splits a path into its segments, decoding any that were percent-encoded.
     pub fn new(string: &str) -> Route {
         let route = string.split('/')
             .filter(|p| p != &"")
-            .map(|p| p.to_owned())
+            .map(|p| percent_decode(p.as_bytes()).decode_utf8_lossy().into_owned())
             .collect::<Vec<String>>();
 
         Route(route)
@@ -30,7 +32,11 @@ impl Route {
         self.0
     }
 
+    /// Joins the segments into a path, percent-encoding any that need it.
     pub fn to_string(self) -> String {
-        "/".to_owned() + &self.to_vec().join("/")
+        let segments = self.to_vec().iter()
+            .map(|s| utf8_percent_encode(s, PATH_SEGMENT_ENCODE_SET).to_string())
+            .collect::<Vec<String>>();
+        "/".to_owned() + &segments.join("/")
     }
 }
@@ -0,0 +1,68 @@
+use ramhorns::{Template, Content};
+use crate::template::base::{Action, Actions, Base, asset};
+use crate::route::Route;
+
+#[derive(Content)]
+struct Blame {
+    title:  String,
+    branch: String,
+    lines:  Vec<Line>,
+}
+
+#[derive(Content)]
+struct Line {
+    ver_no: String,
+    link:   String,
+    text:   String,
+}
+
+pub async fn render(
+    title:  String,
+    branch: String,
+    id:     String,
+    lines:  Vec<(String, usize)>,
+) -> Result<String, String> {
+    // get the templates
+    let base = Template::new(asset("base.html").await?)
+        .ok().ok_or("Could not create base template")?;
+    let template = Template::new(asset("blame.html").await?)
+        .ok().ok_or("Could not create blame template")?;
+
+    let actions = Actions {
+        items: vec![
+            ("arrow_back", Route::over(vec!["perma".to_string(), branch.clone(), "head".to_string(), id.clone()]), "Back"),
+            ("timeline", Route::over(vec!["history".to_string(), branch.clone(), id.clone()]), "History of this Page"),
+        ].into_iter()
+            .map(
+            |action| {
+                let (icon, route, value) = action;
+                Action {
+                    icon:  icon.to_owned(),
+                    link:  route.to_string(),
+                    value: value.to_string(),
+                }
+            }
+            )
+            .collect::<Vec<Action>>(),
+    };
+
+    let lines = lines.into_iter()
+        .map(|(text, ver_no)| Line {
+            ver_no: ver_no.to_string(),
+            link:   Route::over(vec!["perma".to_string(), branch.clone(), ver_no.to_string(), id.clone()]).to_string(),
+            text,
+        })
+        .collect::<Vec<Line>>();
+
+    // flesh them out
+    let blame_data = Blame { title: title.clone(), branch, lines };
+    let blame_rendered = template.render(&blame_data);
+    let base_data = Base {
+        title: "Blaming — ".to_owned() + &title,
+        content: blame_rendered,
+        children: None,
+        actions:  Some(actions),
+    };
+    let base_rendered = base.render(&base_data);
+    return Ok(base_rendered);
+}
@@ -0,0 +1,47 @@
+use ramhorns::{Template, Content};
+use crate::template::base::{Action, Actions, Base, asset};
+
+#[derive(Content)]
+struct Confirm {
+    title:   String,
+    message: String,
+    action:  String,
+    submit:  String,
+}
+
+/// Asks before doing something that is hard to undo.
+/// Submitting posts to `action`, while `back` leads away without doing anything.
+pub async fn render(title: &str, message: String, action: String, submit: &str, back: String) -> Result<String, String> {
+    // get the templates
+    let base = Template::new(asset("base.html").await?)
+        .ok().ok_or("Could not create base template")?;
+    let confirm = Template::new(asset("confirm.html").await?)
+        .ok().ok_or("Could not create confirm template")?;
+
+    let actions = Actions {
+        items: vec![
+            Action {
+                icon:  "arrow_back".to_owned(),
+                link:  back,
+                value: "Back".to_owned(),
+            },
+        ],
+    };
+
+    // flesh them out
+    let confirm_data = Confirm {
+        title:   title.to_owned(),
+        message,
+        action,
+        submit:  submit.to_owned(),
+    };
+    let confirm_rendered = confirm.render(&confirm_data);
+    let base_data = Base {
+        title: title.to_owned(),
+        content: confirm_rendered,
+        children: None,
+        actions:  Some(actions),
+    };
+    let base_rendered = base.render(&base_data);
+    return Ok(base_rendered);
+}
@@ -0,0 +1,120 @@
+use ramhorns::{Template, Content};
+use crate::template::base::{Action, Actions, Base, asset};
+use crate::hrdb::diff::PageDiff;
+use crate::route::Route;
+
+#[derive(Content)]
+struct Diff {
+    title:   String,
+    branch:  String,
+    before:  String,
+    after:   String,
+    summary: Option<Summary>,
+    content: Option<Lines>,
+}
+
+#[derive(Content)]
+struct Summary {
+    items: Vec<Item>,
+}
+
+#[derive(Content)]
+struct Item {
+    item: String,
+}
+
+#[derive(Content)]
+struct Lines {
+    lines: Vec<Line>,
+}
+
+#[derive(Content)]
+struct Line {
+    kind:  String,
+    words: Vec<Word>,
+}
+
+#[derive(Content)]
+struct Word {
+    text:    String,
+    changed: bool,
+}
+
+pub async fn render(
+    title:  String,
+    branch: String,
+    before: String,
+    after:  String,
+    id:     String,
+    diff:   PageDiff,
+) -> Result<String, String> {
+    // get the templates
+    let base = Template::new(asset("base.html").await?)
+        .ok().ok_or("Could not create base template")?;
+    let template = Template::new(asset("diff.html").await?)
+        .ok().ok_or("Could not create diff template")?;
+
+    let actions = Actions {
+        items: vec![
+            ("arrow_back", Route::over(vec!["perma".to_string(), branch.clone(), after.clone(), id.clone()]), "Back"),
+            ("history", Route::over(vec!["perma".to_string(), branch.clone(), before.clone(), id.clone()]), "View the earlier Version"),
+        ].into_iter()
+            .map(
+            |action| {
+                let (icon, route, value) = action;
+                Action {
+                    icon:  icon.to_owned(),
+                    link:  route.to_string(),
+                    value: value.to_string(),
+                }
+            }
+            )
+            .collect::<Vec<Action>>(),
+    };
+
+    let mut items = vec![];
+    if let Some((old, new)) = diff.title {
+        items.push(format!("Renamed from “{}” to “{}”", old, new));
+    }
+    for (key, old, new) in diff.fields.into_iter() {
+        items.push(match (old, new) {
+            (None, Some(n))    => format!("Set {} to “{}”", key, n),
+            (Some(o), None)    => format!("Removed {} (was “{}”)", key, o),
+            (Some(o), Some(n)) => format!("Changed {} from “{}” to “{}”", key, o, n),
+            (None, None)       => continue,
+        });
+    }
+    for (child, added) in diff.children.into_iter() {
+        items.push(format!("{} the page “{}”", if added { "Nested" } else { "Removed" }, child));
+    }
+
+    let content = diff.content.map(|lines| Lines {
+        lines: lines.into_iter()
+            .map(|line| Line {
+                kind:  line.kind.to_owned(),
+                words: line.words.into_iter()
+                    .map(|(text, changed)| Word { text, changed })
+                    .collect::<Vec<Word>>(),
+            })
+            .collect::<Vec<Line>>(),
+    });
+
+    // flesh them out
+    let diff_data = Diff {
+        title: title.clone(),
+        branch,
+        before,
+        after,
+        summary: if items.is_empty() { None } else { Some(Summary { items: items.into_iter().map(|item| Item { item }).collect() }) },
+        content,
+    };
+    let diff_rendered = template.render(&diff_data);
+    let base_data = Base {
+        title: "Comparing — ".to_owned() + &title,
+        content: diff_rendered,
+        children: None,
+        actions:  Some(actions),
+    };
+    let base_rendered = base.render(&base_data);
+    return Ok(base_rendered);
+}
@@ -0,0 +1,59 @@
+use ramhorns::{Template, Content};
+use crate::template::base::{Action, Actions, Base, asset};
+use crate::route::Route;
+
+#[derive(Content)]
+struct Duplicate {
+    title:  String,
+    branch: String,
+    id:     String,
+    destinations: Vec<Destination>,
+}
+
+#[derive(Content)]
+struct Destination {
+    id:       String,
+    title:    String,
+    selected: bool,
+}
+
+pub async fn render(
+    title:  String,
+    branch: String,
+    id:     String,
+    parent: String,
+    destination_pair: Vec<(String, String)>, // (title, id)
+) -> Result<String, String> {
+    // get the templates
+    let base = Template::new(asset("base.html").await?)
+        .ok().ok_or("Could not create base template")?;
+    let duplicate = Template::new(asset("duplicate.html").await?)
+        .ok().ok_or("Could not create duplicate template")?;
+
+    let actions = Actions {
+        items: vec![
+            Action {
+                icon:  "arrow_back".to_owned(),
+                link:  Route::over(vec!["edit".to_string(), branch.clone(), id.clone()]).to_string(),
+                value: "Back".to_owned(),
+            },
+        ],
+    };
+
+    // copies go next to the original unless told otherwise
+    let destinations = destination_pair.into_iter()
+        .map(|(title, id)| Destination { selected: id == parent, id, title })
+        .collect::<Vec<Destination>>();
+
+    // flesh them out
+    let duplicate_data = Duplicate { title: title.clone(), branch, id, destinations };
+    let duplicate_rendered = duplicate.render(&duplicate_data);
+    let base_data = Base {
+        title: "Duplicating — ".to_owned() + &title,
+        content: duplicate_rendered,
+        children: None,
+        actions:  Some(actions),
+    };
+    let base_rendered = base.render(&base_data);
+    return Ok(base_rendered);
+}
@@ -4,18 +4,37 @@ use crate::route::Route;
 
 #[derive(Content)]
 struct Edit {
-    title:  String,
-    old:    String,
-    branch: String,
-    id:     String,
+    title:   String,
+    old:     String,
+    branch:  String,
+    id:      String,
+    version: String,
+    fields:  String,
+    stale:   Option<Stale>,
+    invalid: Option<String>,
 }
 
-pub async fn render(
-    title: String,
-    old:   String,
-    branch: String,
-    id: String,
-) -> Result<String, String> {
+/// Shown when someone else committed while this page was being edited.
+#[derive(Content)]
+struct Stale {
+    diff: String,
+}
+
+/// A page as it is shown in the editor.
+pub struct Draft {
+    pub title:   String,
+    pub content: String,
+    pub fields:  String, // the page's front matter block
+    pub branch:  String,
+    pub id:      String,
+    pub version: usize, // the version number the editor is based on
+}
+
+/// If `stale_since` is the version the previous attempt was based on,
+/// the editor explains why it was not saved, and links to what changed since.
+/// If `invalid` is given, the previous attempt had fields that could not be read.
+pub async fn render(draft: Draft, stale_since: Option<usize>, invalid: Option<String>) -> Result<String, String> {
+    let Draft { title, content: old, fields, branch, id, version } = draft;
     // get the templates
     let base = Template::new(asset("base.html").await?)
         .ok().ok_or("Could not create base template")?;
@@ -26,6 +45,12 @@ pub async fn render(
         items: vec![
             ("arrow_back", Route::over(vec!["perma".to_string(), branch.clone(), "head".to_string(), id.clone()]), "Back"),
             ("add", Route::over(vec!["create".to_string(), branch.clone(), id.clone()]), "Create a new page"),
+            ("low_priority", Route::over(vec!["relocate".to_string(), branch.clone(), id.clone()]), "Move this page"),
+            ("content_copy", Route::over(vec!["duplicate".to_string(), branch.clone(), id.clone()]), "Duplicate this page"),
+            ("arrow_upward", Route::over(vec!["order".to_string(), branch.clone(), id.clone(), "up".to_string()]), "Move up among its siblings"),
+            ("arrow_downward", Route::over(vec!["order".to_string(), branch.clone(), id.clone(), "down".to_string()]), "Move down among its siblings"),
+            ("sort_by_alpha", Route::over(vec!["order".to_string(), branch.clone(), id.clone(), "title".to_string()]), "Sort children by title"),
+            ("event", Route::over(vec!["order".to_string(), branch.clone(), id.clone(), "date".to_string()]), "Sort children by date, newest first"),
             ("delete", Route::over(vec!["delete".to_string(), branch.clone(), id.clone()]), "Delete this page"),
         ].into_iter()
             .map(
@@ -42,7 +67,19 @@ pub async fn render(
     };
 
     // flesh them out
-    let edit_data = Edit { title: title.clone(), old, branch, id };
+    let stale = stale_since.map(|since| Stale {
+        diff: Route::over(vec!["diff".to_string(), branch.clone(), since.to_string(), version.to_string(), id.clone()]).to_string(),
+    });
+    let edit_data = Edit {
+        title: title.clone(),
+        old,
+        branch,
+        id,
+        version: version.to_string(),
+        fields,
+        stale,
+        invalid,
+    };
     let edit_rendered = edit.render(&edit_data);
     let base_data = Base {
         title: "Editing — ".to_owned() + &title,
@@ -0,0 +1,67 @@
+use ramhorns::{Template, Content};
+use crate::template::base::{Base, asset};
+use crate::hrdb::gc::Report;
+
+#[derive(Content)]
+struct Gc {
+    days:      String,
+    dry_run:   bool,
+    reachable: usize,
+    count:     usize,
+    pending:   usize,
+    deferred:  usize,
+    has_deferred: bool,
+    incomplete: bool,
+    pruned:    Vec<Pruned>,
+    swept:     Vec<Swept>,
+}
+
+#[derive(Content)]
+struct Pruned {
+    branch: String,
+    count:  usize,
+}
+
+#[derive(Content)]
+struct Swept {
+    address: String,
+}
+
+pub async fn render(days: Option<u64>, report: Report) -> Result<String, String> {
+    // get the templates
+    let base = Template::new(asset("base.html").await?)
+        .ok().ok_or("Could not create base template")?;
+    let gc = Template::new(asset("gc.html").await?)
+        .ok().ok_or("Could not create gc template")?;
+
+    let pruned = report.pruned.into_iter()
+        .map(|(branch, count)| Pruned { branch, count })
+        .collect::<Vec<Pruned>>();
+    let swept = report.swept.into_iter()
+        .map(|address| Swept { address })
+        .collect::<Vec<Swept>>();
+
+    // flesh them out
+    let title = if report.dry_run { "Garbage Collection" } else { "Garbage Collected" };
+    let gc_data = Gc {
+        days:      days.map(|d| d.to_string()).unwrap_or_default(),
+        dry_run:   report.dry_run,
+        reachable: report.reachable,
+        count:     swept.len(),
+        pending:   report.pending,
+        deferred:  report.deferred,
+        has_deferred: report.deferred > 0,
+        incomplete: report.incomplete,
+        pruned,
+        swept,
+    };
+    let gc_rendered = gc.render(&gc_data);
+    let base_data = Base {
+        title: title.to_owned(),
+        content: gc_rendered,
+        children: None,
+        actions:  None,
+    };
+    let base_rendered = base.render(&base_data);
+    return Ok(base_rendered);
+}
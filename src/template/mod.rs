@@ -4,3 +4,13 @@ pub mod edit;
 pub mod table;
 pub mod error;
 pub mod auth;
+pub mod fork;
+pub mod rename;
+pub mod release;
+pub mod relocate;
+pub mod duplicate;
+pub mod search;
+pub mod diff;
+pub mod blame;
+pub mod gc;
+pub mod confirm;
@@ -1,25 +1,68 @@
 use ramhorns::{Template, Content};
 use crate::template::base::{Child, Children, Action, Actions, Base, asset};
 use crate::route::Route;
+use crate::hrdb::fields::Value;
 
 #[derive(Content)]
 struct Page {
     title:   String,
     #[md]
     content: String,
+    has_fields: bool,
+    fields:  Vec<Field>,
+    has_pick: bool,
+    pick:    String, // where to post to carry this version's change to master
+}
+
+/// A field, typed so templates can show each kind differently.
+/// `kind` is one of "date", "bool", "list", or "text".
+#[derive(Content)]
+struct Field {
+    key:   String,
+    kind:  &'static str,
+    value: String,
+    items: Vec<Item>,
+}
+
+#[derive(Content)]
+struct Item {
+    item: String,
+}
+
+impl Field {
+    fn new(key: String, raw: &str) -> Field {
+        let value = Value::parse(raw).unwrap_or(Value::Text(raw.to_owned()));
+        let items = match &value {
+            Value::List(items) => items.iter().map(|i| Item { item: i.to_owned() }).collect(),
+            _ => vec![],
+        };
+        let shown = match &value {
+            Value::Text(t) => t.to_owned(),
+            v => v.to_string(),
+        };
+        Field { key, kind: value.kind(), value: shown, items }
+    }
+}
+
+/// Where a page being shown is: which version of which branch,
+/// and whether that is the head, or the page the root.
+pub struct Place {
+    pub branch:  String,
+    pub vn:      usize,
+    pub iden:    String,
+    pub is_head: bool,
+    pub is_root: bool,
+    pub parent:  String,
 }
 
 pub async fn render(
     title:   String,
     content: String,
-    branch:  String,
-    vn:      usize,
-    iden:    String,
-    is_head: bool,
-    is_root: bool,
-    parent:  String,
+    fields:  Vec<(String, String)>, // (key, value), in key order
+    place:   Place,
     child_pair: Vec<(String, String)>, // (title, id)
 ) -> Result<String, String> {
+    let Place { branch, vn, iden, is_head, is_root, parent } = place;
     // get the templates
     let base = Template::new(asset("base.html").await?)
         .ok().ok_or("Could not create base template")?;
@@ -41,8 +84,29 @@ pub async fn render(
     items.append(&mut vec![
         ("push_pin", Route::over(vec!["perma".to_string(), branch.clone(), vn.to_string(), id.clone()]), "Permalink this Version"),
         ("edit", Route::over(vec!["edit".to_string(), branch.clone(), iden.clone()]), "Edit this Page"),
+        ("call_split", Route::over(vec!["fork".to_string(), branch.clone(), vn.to_string()]), "Fork from this Version"),
+        ("new_releases", Route::over(vec!["release".to_string(), branch.clone(), vn.to_string()]), "Release this Version"),
+        ("timeline", Route::over(vec!["history".to_string(), branch.clone(), iden.clone()]), "History of this Page"),
+        ("format_list_numbered", Route::over(vec!["blame".to_string(), branch.clone(), iden.clone()]), "Blame this Page"),
     ]);
+    if vn > 0 {
+        items.push((
+            "compare_arrows",
+            Route::over(vec!["diff".to_string(), branch.clone(), (vn - 1).to_string(), vn.to_string(), iden.clone()]),
+            "Compare with the previous Version"
+        ));
+    }
     if !is_head {
+        items.push((
+            "restore",
+            Route::over(vec!["restore".to_string(), branch.clone(), vn.to_string(), id.clone()]),
+            "Restore this Page"
+        ));
+        items.push((
+            "settings_backup_restore",
+            Route::over(vec!["reset".to_string(), branch.clone(), vn.to_string()]),
+            "Reset Branch to this Version"
+        ));
         items.push((
             "update",
             Route::over(vec!["perma".to_string(), branch.clone(), "head".to_string(), id.clone()]),
@@ -77,7 +141,22 @@ pub async fn render(
     };
 
     // flesh them out
-    let page_data = Page { title: title.clone(), content };
+    let fields = fields.into_iter()
+        .map(|(key, raw)| Field::new(key, &raw))
+        .collect::<Vec<Field>>();
+    let pick = match vn {
+        0 => None,
+        _ if branch == "master" => None,
+        _ => Some(Route::over(vec!["pick".to_string(), branch.clone(), (vn - 1).to_string(), vn.to_string(), iden.clone(), "master".to_string()])),
+    };
+    let page_data = Page {
+        title: title.clone(),
+        content,
+        has_fields: !fields.is_empty(),
+        fields,
+        has_pick: pick.is_some(),
+        pick: pick.map(|r| r.to_string()).unwrap_or_default(),
+    };
     let page_rendered = page.render(&page_data);
     let base_data = Base {
         title,
@@ -0,0 +1,39 @@
+use ramhorns::{Template, Content};
+use crate::template::base::{Action, Actions, Base, asset};
+use crate::route::Route;
+
+#[derive(Content)]
+struct Release {
+    branch: String,
+    ver_no: String,
+}
+
+pub async fn render(branch: String, ver_no: String) -> Result<String, String> {
+    // get the templates
+    let base = Template::new(asset("base.html").await?)
+        .ok().ok_or("Could not create base template")?;
+    let release = Template::new(asset("release.html").await?)
+        .ok().ok_or("Could not create release template")?;
+
+    let actions = Actions {
+        items: vec![
+            Action {
+                icon:  "arrow_back".to_owned(),
+                link:  Route::over(vec!["perma".to_string(), branch.clone(), ver_no.clone(), "root".to_string()]).to_string(),
+                value: "Back".to_owned(),
+            },
+        ],
+    };
+
+    // flesh them out
+    let release_data = Release { branch: branch.clone(), ver_no };
+    let release_rendered = release.render(&release_data);
+    let base_data = Base {
+        title: "Releasing — ".to_owned() + &branch,
+        content: release_rendered,
+        children: None,
+        actions:  Some(actions),
+    };
+    let base_rendered = base.render(&base_data);
+    return Ok(base_rendered);
+}
@@ -0,0 +1,56 @@
+use ramhorns::{Template, Content};
+use crate::template::base::{Action, Actions, Base, asset};
+use crate::route::Route;
+
+#[derive(Content)]
+struct Relocate {
+    title:  String,
+    branch: String,
+    id:     String,
+    destinations: Vec<Destination>,
+}
+
+#[derive(Content)]
+struct Destination {
+    id:    String,
+    title: String,
+}
+
+pub async fn render(
+    title:  String,
+    branch: String,
+    id:     String,
+    destination_pair: Vec<(String, String)>, // (title, id)
+) -> Result<String, String> {
+    // get the templates
+    let base = Template::new(asset("base.html").await?)
+        .ok().ok_or("Could not create base template")?;
+    let relocate = Template::new(asset("relocate.html").await?)
+        .ok().ok_or("Could not create relocate template")?;
+
+    let actions = Actions {
+        items: vec![
+            Action {
+                icon:  "arrow_back".to_owned(),
+                link:  Route::over(vec!["edit".to_string(), branch.clone(), id.clone()]).to_string(),
+                value: "Back".to_owned(),
+            },
+        ],
+    };
+
+    let destinations = destination_pair.into_iter()
+        .map(|(title, id)| Destination { id, title })
+        .collect::<Vec<Destination>>();
+
+    // flesh them out
+    let relocate_data = Relocate { title: title.clone(), branch, id, destinations };
+    let relocate_rendered = relocate.render(&relocate_data);
+    let base_data = Base {
+        title: "Moving — ".to_owned() + &title,
+        content: relocate_rendered,
+        children: None,
+        actions:  Some(actions),
+    };
+    let base_rendered = base.render(&base_data);
+    return Ok(base_rendered);
+}
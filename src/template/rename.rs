@@ -0,0 +1,38 @@
+use ramhorns::{Template, Content};
+use crate::template::base::{Action, Actions, Base, asset};
+use crate::route::Route;
+
+#[derive(Content)]
+struct Rename {
+    branch: String,
+}
+
+pub async fn render(branch: String) -> Result<String, String> {
+    // get the templates
+    let base = Template::new(asset("base.html").await?)
+        .ok().ok_or("Could not create base template")?;
+    let rename = Template::new(asset("rename.html").await?)
+        .ok().ok_or("Could not create rename template")?;
+
+    let actions = Actions {
+        items: vec![
+            Action {
+                icon:  "arrow_back".to_owned(),
+                link:  Route::over(vec!["branches".to_string()]).to_string(),
+                value: "Back".to_owned(),
+            },
+        ],
+    };
+
+    // flesh them out
+    let rename_data = Rename { branch: branch.clone() };
+    let rename_rendered = rename.render(&rename_data);
+    let base_data = Base {
+        title: "Renaming — ".to_owned() + &branch,
+        content: rename_rendered,
+        children: None,
+        actions:  Some(actions),
+    };
+    let base_rendered = base.render(&base_data);
+    return Ok(base_rendered);
+}
@@ -0,0 +1,47 @@
+use ramhorns::{Template, Content};
+use crate::template::base::{Base, asset};
+
+#[derive(Content)]
+struct Search {
+    query:    String,
+    branch:   String,
+    searched: bool,
+    results:  Vec<Result_>,
+}
+
+#[derive(Content)]
+struct Result_ {
+    title:   String,
+    link:    String,
+    snippet: String,
+}
+
+pub async fn render(
+    query:  String,
+    branch: String,
+    result_triple: Option<Vec<(String, String, String)>>, // (title, link, snippet)
+) -> Result<String, String> {
+    // get the templates
+    let base = Template::new(asset("base.html").await?)
+        .ok().ok_or("Could not create base template")?;
+    let search = Template::new(asset("search.html").await?)
+        .ok().ok_or("Could not create search template")?;
+
+    let searched = result_triple.is_some();
+    let results = result_triple.unwrap_or_default().into_iter()
+        .map(|(title, link, snippet)| Result_ { title, link, snippet })
+        .collect::<Vec<Result_>>();
+
+    // flesh them out
+    let title = if searched { "Searching — ".to_owned() + &query } else { "Search".to_owned() };
+    let search_data = Search { query, branch, searched, results };
+    let search_rendered = search.render(&search_data);
+    let base_data = Base {
+        title,
+        content: search_rendered,
+        children: None,
+        actions:  None,
+    };
+    let base_rendered = base.render(&base_data);
+    return Ok(base_rendered);
+}
@@ -1,11 +1,12 @@
 use ramhorns::{Template, Content};
-use crate::template::base::{Children, Actions, Base, asset};
+use crate::template::base::{Base, asset};
 
 #[derive(Debug, Content)]
 struct Table {
     title: String,
     columns: Vec<Item>,
     rows: Vec<Row>,
+    pages: Option<Pages>,
 }
 
 #[derive(Debug, Content)]
@@ -13,15 +14,47 @@ struct Row {
     items: Vec<Item>,
 }
 
+/// A single cell, optionally linking somewhere.
 #[derive(Debug, Content)]
-struct Item {
+pub struct Item {
     item: String,
+    link: Option<String>,
+}
+
+impl Item {
+    pub fn text(item: String) -> Item {
+        Item { item, link: None }
+    }
+
+    pub fn link(item: String, link: String) -> Item {
+        Item { item, link: Some(link) }
+    }
+}
+
+/// Links to the neighbouring pages of a long table.
+#[derive(Debug, Content)]
+pub struct Pages {
+    pub newer: Option<String>,
+    pub older: Option<String>,
 }
 
 pub async fn render(
     title: String,
     c: Vec<String>,
     r: Vec<Vec<String>>,
+) -> Result<String, String> {
+    let rows = r.into_iter()
+        .map(|row| row.into_iter().map(Item::text).collect::<Vec<Item>>())
+        .collect::<Vec<Vec<Item>>>();
+
+    render_items(title, c, rows, None).await
+}
+
+pub async fn render_items(
+    title: String,
+    c: Vec<String>,
+    r: Vec<Vec<Item>>,
+    pages: Option<Pages>,
 ) -> Result<String, String> {
     // get the templates
     let base = Template::new(asset("base.html").await?)
@@ -30,19 +63,14 @@ pub async fn render(
         .ok().ok_or("Could not create table template")?;
 
     let columns = c.into_iter()
-        .map(|item| Item { item })
+        .map(Item::text)
         .collect::<Vec<Item>>();
     let rows    = r.into_iter()
-        .map(|row| Row {
-            items: row.into_iter()
-                .map(|item| Item { item })
-                .collect::<Vec<Item>>()
-            }
-        )
+        .map(|items| Row { items })
         .collect::<Vec<Row>>();
 
     // flesh them out
-    let table_data = Table { title: title.clone(), columns, rows };
+    let table_data = Table { title: title.clone(), columns, rows, pages };
     // log(&format!("table {:?}", table_data));
     let table_rendered = table.render(&table_data);
     let base_data = Base {